@@ -21,6 +21,10 @@ use audioviz::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::dmx::{
+    master::MasterState, monitor::UniverseUpdate, output::OutputStatus, walk::WalkState,
+};
+
 
 fn map(x: isize, in_min: isize, in_max: isize, out_min: isize, out_max: isize) -> usize {
     let divisor = (in_max - in_min).max(1);
@@ -170,6 +174,12 @@ pub enum Command {
 
 pub enum SystemMessage {
     LoopSpeed(Duration),
+    DmxFrameRate(f32),
+    DmxOutputStatus { universe: u16, status: OutputStatus },
+    Universe(UniverseUpdate),
+    Masters(MasterState),
+    /// Current position of the channel walk, `None` once it stopped.
+    Walk(Option<WalkState>),
 }
 
 // <<<<<<< Updated upstream
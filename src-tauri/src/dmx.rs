@@ -0,0 +1,184 @@
+pub mod interfaces;
+pub mod manual;
+pub mod master;
+pub mod merge;
+pub mod monitor;
+pub mod network;
+pub mod output;
+pub mod recording;
+pub mod walk;
+
+use std::{io, time::Duration};
+
+use interfaces::{DmxTiming, InterfaceDefinition, Protocol};
+use serde::Serialize;
+use serialport::SerialPort;
+
+/// ENTTEC Pro message label for "Output Only Send DMX Packet Request".
+const ENTTEC_PRO_SEND_DMX_LABEL: u8 = 6;
+/// ENTTEC Pro message label for "Set Widget Parameters Request".
+const ENTTEC_PRO_SET_PARAMETERS_LABEL: u8 = 3;
+/// Break and MAB are configured in units of 10.67us on ENTTEC Pro widgets.
+const ENTTEC_PRO_TIME_UNIT_US: f32 = 10.67;
+const ENTTEC_PRO_START_OF_MESSAGE: u8 = 0x7E;
+const ENTTEC_PRO_END_OF_MESSAGE: u8 = 0xE7;
+
+/// Number of slots (channels) in a DMX universe.
+pub const UNIVERSE_SIZE: usize = 512;
+
+/// Universes fixtures can be patched to. Frames are kept for every universe up to the
+/// highest one in use, so this bounds the memory copied on every tick.
+pub const MAX_UNIVERSES: u16 = 64;
+
+/// Channel values of one universe. Channel 1 is at index 0.
+pub type Frame = [u8; UNIVERSE_SIZE];
+
+/// Where the value of a channel in the output frame came from.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Owner {
+    Engine,
+    Playback,
+    ArtNet,
+    Sacn,
+    Manual,
+    /// Blackout or panic.
+    Master,
+    /// Channel walk test mode.
+    Walk,
+}
+
+/// Owner of every channel of one universe, laid out like [`Frame`].
+pub type Owners = [Owner; UNIVERSE_SIZE];
+
+/// The effects that wrote the channels of the engine's frames.
+#[derive(Debug, Clone, Default)]
+pub struct EffectOwners {
+    /// Effect and layer names, e.g. "Wash (Base)".
+    pub names: Vec<String>,
+    /// Per universe and channel an index into `names`, `None` for channels no effect wrote.
+    pub channels: Vec<[Option<u16>; UNIVERSE_SIZE]>,
+}
+
+impl EffectOwners {
+    /// Name of the effect that wrote the channel, starting at 0.
+    pub fn name(&self, universe: usize, channel: usize) -> Option<&str> {
+        let index = self.channels.get(universe)?[channel]?;
+        self.names.get(index as usize).map(String::as_str)
+    }
+}
+
+/// Payload of the ENTTEC Pro "Set Widget Parameters Request" for the timing,
+/// with break and MAB in the range the widget accepts.
+pub fn enttec_pro_parameters(timing: &DmxTiming) -> [u8; 5] {
+    let break_units = (timing.break_us as f32 / ENTTEC_PRO_TIME_UNIT_US).round() as u8;
+    let mab_units = (timing.mark_after_break_us as f32 / ENTTEC_PRO_TIME_UNIT_US).round() as u8;
+
+    [
+        0,
+        0,
+        break_units.clamp(9, 127),
+        mab_units.clamp(1, 127),
+        // Packets per second, 0 means as fast as possible.
+        // Pacing is done by the output thread.
+        0,
+    ]
+}
+
+pub struct DmxUniverse {
+    serial: Box<dyn SerialPort>,
+    interface: InterfaceDefinition,
+    timing: DmxTiming,
+    channels: [u8; 513],
+}
+
+impl DmxUniverse {
+    pub fn open(port_path: &str, interface: InterfaceDefinition) -> io::Result<Self> {
+        let timing = interface.timing.clamped();
+
+        let port = serialport::new(port_path, 250000)
+            .timeout(Duration::from_millis(timing.timeout_ms))
+            .stop_bits(serialport::StopBits::Two)
+            .data_bits(serialport::DataBits::Eight)
+            .parity(serialport::Parity::None)
+            .open()?;
+
+        let mut universe = Self {
+            serial: port,
+            interface,
+            timing,
+            channels: [0; 513],
+        };
+        universe.apply_widget_parameters()?;
+        Ok(universe)
+    }
+
+    /// Overrides the timing of the interface definition for this output only.
+    pub fn set_timing(&mut self, timing: DmxTiming) -> io::Result<()> {
+        self.timing = timing.clamped();
+        self.serial
+            .set_timeout(Duration::from_millis(self.timing.timeout_ms))?;
+        self.apply_widget_parameters()
+    }
+
+    pub fn timing(&self) -> DmxTiming {
+        self.timing
+    }
+
+    /// Sets the slots sent by the next [`DmxUniverse::write_to_serial`]. The start code stays 0.
+    pub fn set_frame(&mut self, frame: &Frame) {
+        self.channels[1..].copy_from_slice(frame);
+    }
+
+    /// ENTTEC Pro widgets generate break and MAB themselves, they have to be told about timing.
+    fn apply_widget_parameters(&mut self) -> io::Result<()> {
+        if self.interface.protocol != Protocol::EnttecPro {
+            return Ok(());
+        }
+
+        self.write_enttec_message(
+            ENTTEC_PRO_SET_PARAMETERS_LABEL,
+            &enttec_pro_parameters(&self.timing),
+        )
+    }
+
+    fn write_enttec_message(&mut self, label: u8, payload: &[u8]) -> io::Result<()> {
+        let len = payload.len() as u16;
+        let mut message = Vec::with_capacity(payload.len() + 5);
+        message.push(ENTTEC_PRO_START_OF_MESSAGE);
+        message.push(label);
+        message.extend_from_slice(&len.to_le_bytes());
+        message.extend_from_slice(payload);
+        message.push(ENTTEC_PRO_END_OF_MESSAGE);
+        self.serial.write_all(&message)
+    }
+
+    fn send_break(&self, duration: Duration) -> io::Result<()> {
+        self.serial.set_break()?;
+        spin_sleep::sleep(duration);
+        self.serial.clear_break()?;
+        Ok(())
+    }
+
+    /// Fails if the port went away, e.g. because the cable was unplugged.
+    pub fn write_to_serial(&mut self) -> io::Result<()> {
+        // Start code plus the configured number of slots.
+        let len = self.timing.slots as usize + 1;
+
+        match self.interface.protocol {
+            Protocol::OpenDmx => {
+                self.send_break(Duration::from_micros(self.timing.break_us))?;
+                spin_sleep::sleep(Duration::from_micros(self.timing.mark_after_break_us));
+                self.serial.write_all(&self.channels[..len])?;
+            }
+            Protocol::EnttecPro => {
+                let channels = self.channels;
+                self.write_enttec_message(ENTTEC_PRO_SEND_DMX_LABEL, &channels[..len])?;
+            }
+        }
+        self.serial.flush()?;
+
+        spin_sleep::sleep(Duration::from_micros(self.timing.inter_frame_gap_us));
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serialport::{SerialPortInfo, SerialPortType, UsbPortInfo};
+
+use super::UNIVERSE_SIZE;
+
+/// Name of the file (inside the app config directory) holding the interface registry.
+pub const INTERFACES_FILE: &str = "interfaces.json";
+
+/// How frames are framed on the wire for a given interface.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Dumb FTDI-style cable: the host generates break and mark-after-break itself
+    /// and then writes the raw slots at 250 kBaud.
+    OpenDmx,
+    /// ENTTEC DMX USB Pro compatible widget: frames are wrapped in a
+    /// `0x7E <label> <len> ... 0xE7` message and the widget handles timing.
+    EnttecPro,
+}
+
+/// Line timing of a DMX output. Missing fields fall back to the defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DmxTiming {
+    pub break_us: u64,
+    pub mark_after_break_us: u64,
+    /// Idle line time after the last slot, before the next break.
+    pub inter_frame_gap_us: u64,
+    /// Number of slots sent after the start code, between 1 and 512.
+    /// Shorter frames allow higher refresh rates when only few channels are patched.
+    pub slots: u16,
+    /// Serial port write timeout. A full frame takes about 23ms at 250 kBaud.
+    pub timeout_ms: u64,
+}
+
+impl DmxTiming {
+    /// DMX512-A requires a break of at least 92us and a MAB of at least 12us.
+    pub const MIN_BREAK_US: u64 = 92;
+    pub const MIN_MARK_AFTER_BREAK_US: u64 = 12;
+
+    pub fn clamped(self) -> Self {
+        Self {
+            break_us: self.break_us.max(Self::MIN_BREAK_US),
+            mark_after_break_us: self.mark_after_break_us.max(Self::MIN_MARK_AFTER_BREAK_US),
+            inter_frame_gap_us: self.inter_frame_gap_us,
+            slots: self.slots.clamp(1, UNIVERSE_SIZE as u16),
+            timeout_ms: self.timeout_ms.max(1),
+        }
+    }
+}
+
+impl Default for DmxTiming {
+    fn default() -> Self {
+        Self {
+            break_us: 100,
+            mark_after_break_us: 100,
+            inter_frame_gap_us: 0,
+            slots: UNIVERSE_SIZE as u16,
+            timeout_ms: 50,
+        }
+    }
+}
+
+/// A user-editable description of a USB DMX interface.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceDefinition {
+    pub name: String,
+    pub vid: u16,
+    pub pid: u16,
+    /// Only match the interface with this exact serial number.
+    /// Useful when several identical cables are connected.
+    #[serde(default)]
+    pub serial_number: Option<String>,
+    pub protocol: Protocol,
+    #[serde(default)]
+    pub timing: DmxTiming,
+}
+
+impl InterfaceDefinition {
+    pub fn matches(&self, usb: &UsbPortInfo) -> bool {
+        if self.vid != usb.vid || self.pid != usb.pid {
+            return false;
+        }
+
+        match &self.serial_number {
+            Some(serial) => usb.serial_number.as_ref() == Some(serial),
+            None => true,
+        }
+    }
+}
+
+pub fn default_interfaces() -> Vec<InterfaceDefinition> {
+    vec![InterfaceDefinition {
+        name: "Eurolite USB-DMX512-PRO Cable Interface".to_string(),
+        vid: 0x0403,
+        pid: 0x6001,
+        serial_number: None,
+        protocol: Protocol::OpenDmx,
+        timing: DmxTiming::default(),
+    }]
+}
+
+/// Loads the interface registry, falling back to the built-in defaults if the file
+/// does not exist yet or cannot be parsed.
+pub fn load(path: &Path) -> Vec<InterfaceDefinition> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return default_interfaces(),
+        Err(err) => {
+            eprintln!("[dmx] Failed to read {}: {err}", path.display());
+            return default_interfaces();
+        }
+    };
+
+    match serde_json::from_str(&raw) {
+        Ok(definitions) => definitions,
+        Err(err) => {
+            eprintln!("[dmx] Invalid interface registry {}: {err}", path.display());
+            default_interfaces()
+        }
+    }
+}
+
+pub fn save(path: &Path, definitions: &[InterfaceDefinition]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let raw = serde_json::to_string_pretty(definitions)?;
+    fs::write(path, raw)
+}
+
+/// Returns the first registered interface which describes the given port.
+pub fn definition_for<'a>(
+    definitions: &'a [InterfaceDefinition],
+    port: &SerialPortInfo,
+) -> Option<&'a InterfaceDefinition> {
+    let SerialPortType::UsbPort(usb) = &port.port_type else {
+        return None;
+    };
+
+    definitions.iter().find(|d| d.matches(usb))
+}
+
+/// Scans all serial ports and returns the first one matching a registered interface.
+pub fn find_port(
+    definitions: &[InterfaceDefinition],
+) -> Option<(SerialPortInfo, InterfaceDefinition)> {
+    let ports = match serialport::available_ports() {
+        Ok(ports) => ports,
+        Err(err) => {
+            eprintln!("[dmx] Failed to list serial ports: {err}");
+            return None;
+        }
+    };
+
+    first_match(definitions, ports)
+}
+
+/// Returns the first of the ports matching a registered interface.
+pub fn first_match(
+    definitions: &[InterfaceDefinition],
+    ports: Vec<SerialPortInfo>,
+) -> Option<(SerialPortInfo, InterfaceDefinition)> {
+    ports.into_iter().find_map(|port| {
+        let definition = definition_for(definitions, &port)?.clone();
+        Some((port, definition))
+    })
+}
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use super::{Frame, Owner, Owners, UNIVERSE_SIZE};
+
+/// Channel values set by hand, e.g. from faders in the frontend.
+/// They override everything else until they are released.
+#[derive(Default)]
+pub struct ManualOverrides {
+    universes: HashMap<u16, [Option<u8>; UNIVERSE_SIZE]>,
+}
+
+impl ManualOverrides {
+    /// Sets consecutive channels beginning at `start` (starting at 1).
+    /// Values beyond the end of the universe are ignored.
+    pub fn set(&mut self, universe: u16, start: u16, values: &[u8]) {
+        let channels = self
+            .universes
+            .entry(universe)
+            .or_insert([None; UNIVERSE_SIZE]);
+
+        let start = start.max(1) as usize - 1;
+        for (slot, value) in channels.iter_mut().skip(start).zip(values) {
+            *slot = Some(*value);
+        }
+    }
+
+    /// Hands `count` channels beginning at `start` (starting at 1) back to the engine.
+    pub fn release(&mut self, universe: u16, start: u16, count: u16) {
+        let Some(channels) = self.universes.get_mut(&universe) else {
+            return;
+        };
+
+        let start = start.max(1) as usize - 1;
+        for slot in channels.iter_mut().skip(start).take(count as usize) {
+            *slot = None;
+        }
+
+        if channels.iter().all(|c| c.is_none()) {
+            self.universes.remove(&universe);
+        }
+    }
+
+    pub fn release_all(&mut self) {
+        self.universes.clear();
+    }
+
+    pub fn apply(&self, frames: &mut Vec<Frame>, owners: &mut Vec<Owners>) {
+        for (&universe, channels) in &self.universes {
+            let index = universe as usize;
+            if frames.len() <= index {
+                frames.resize(index + 1, [0; UNIVERSE_SIZE]);
+            }
+            if owners.len() <= index {
+                owners.resize(index + 1, [Owner::Engine; UNIVERSE_SIZE]);
+            }
+
+            for (channel, value) in channels.iter().enumerate() {
+                if let Some(value) = value {
+                    frames[index][channel] = *value;
+                    owners[index][channel] = Owner::Manual;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Frame, Owner, Owners, UNIVERSE_SIZE};
+
+/// What happens to the output when panic is pressed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PanicMode {
+    /// Holds the last sent frames, ignoring every source until released.
+    Freeze,
+    /// Fades every channel from the last sent frames to zero.
+    #[serde(rename_all = "camelCase")]
+    Fade { duration_ms: u64 },
+}
+
+/// Global output controls, reported to the frontend whenever they change.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MasterState {
+    /// Scales intensity channels, 0.0 to 1.0.
+    pub grand_master: f32,
+    pub blackout: bool,
+    pub panic: Option<PanicMode>,
+}
+
+struct Panic {
+    mode: PanicMode,
+    frames: Vec<Frame>,
+    since: Instant,
+}
+
+/// Grand master, blackout and panic, applied last before the frames are sent out.
+pub struct Masters {
+    grand_master: f32,
+    blackout: bool,
+    panic: Option<Panic>,
+    /// Dimmer and intensity channels (starting at 1) per universe.
+    /// The grand master and blackout only touch these, so that positions and colors stay put.
+    intensity_channels: HashMap<u16, Vec<u16>>,
+}
+
+impl Default for Masters {
+    fn default() -> Self {
+        Self {
+            grand_master: 1.0,
+            blackout: false,
+            panic: None,
+            intensity_channels: HashMap::new(),
+        }
+    }
+}
+
+impl Masters {
+    pub fn state(&self) -> MasterState {
+        MasterState {
+            grand_master: self.grand_master,
+            blackout: self.blackout,
+            panic: self.panic.as_ref().map(|p| p.mode),
+        }
+    }
+
+    pub fn set_grand_master(&mut self, level: f32) {
+        self.grand_master = level.clamp(0.0, 1.0);
+    }
+
+    pub fn set_blackout(&mut self, blackout: bool) {
+        self.blackout = blackout;
+    }
+
+    /// Starts from `sent`, the frames as they are currently on the wire.
+    pub fn panic(&mut self, mode: PanicMode, sent: &[Frame]) {
+        self.panic = Some(Panic {
+            mode,
+            frames: sent.to_vec(),
+            since: Instant::now(),
+        });
+    }
+
+    pub fn release_panic(&mut self) {
+        self.panic = None;
+    }
+
+    pub fn set_intensity_channels(&mut self, universe: u16, channels: Vec<u16>) {
+        if channels.is_empty() {
+            self.intensity_channels.remove(&universe);
+        } else {
+            self.intensity_channels.insert(universe, channels);
+        }
+    }
+
+    pub fn apply(&self, frames: &mut Vec<Frame>, owners: &mut Vec<Owners>) {
+        if let Some(panic) = &self.panic {
+            let level = match panic.mode {
+                PanicMode::Freeze => 1.0,
+                PanicMode::Fade { duration_ms } => {
+                    let duration = Duration::from_millis(duration_ms.max(1));
+                    1.0 - (panic.since.elapsed().as_secs_f32() / duration.as_secs_f32()).min(1.0)
+                }
+            };
+
+            *frames = panic
+                .frames
+                .iter()
+                .map(|frame| frame.map(|value| (value as f32 * level).round() as u8))
+                .collect();
+            *owners = vec![[Owner::Master; UNIVERSE_SIZE]; frames.len()];
+            return;
+        }
+
+        if !self.blackout && self.grand_master >= 1.0 {
+            return;
+        }
+
+        for (universe, (frame, owners)) in frames.iter_mut().zip(owners.iter_mut()).enumerate() {
+            let channels = self.intensity_channels.get(&(universe as u16));
+
+            if self.blackout {
+                match channels {
+                    Some(channels) => {
+                        for index in channels.iter().filter_map(|&c| channel_index(c)) {
+                            frame[index] = 0;
+                            owners[index] = Owner::Master;
+                        }
+                    }
+                    // Without known intensity channels, the only safe blackout is a dark universe.
+                    None => {
+                        *frame = [0; UNIVERSE_SIZE];
+                        *owners = [Owner::Master; UNIVERSE_SIZE];
+                    }
+                }
+                continue;
+            }
+
+            for index in channels
+                .into_iter()
+                .flatten()
+                .filter_map(|&c| channel_index(c))
+            {
+                frame[index] = (frame[index] as f32 * self.grand_master).round() as u8;
+            }
+        }
+    }
+}
+
+fn channel_index(channel: u16) -> Option<usize> {
+    let index = (channel as usize).checked_sub(1)?;
+    (index < UNIVERSE_SIZE).then_some(index)
+}
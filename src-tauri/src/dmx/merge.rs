@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{network::NetworkInputs, Frame, Owner, Owners, UNIVERSE_SIZE};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMode {
+    /// Highest takes precedence.
+    Htp,
+    /// Latest takes precedence: whichever side changed the channel last wins.
+    Ltp,
+    /// The side with the higher priority wins, equal priorities are merged HTP.
+    Priority,
+}
+
+fn default_priority() -> u8 {
+    100
+}
+
+/// How the external console and Blaulicht share a range of channels.
+/// Channels outside of every range are driven by Blaulicht alone.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeRange {
+    pub universe: u16,
+    /// First channel of the range, starting at 1.
+    pub start: u16,
+    /// Last channel of the range, inclusive.
+    pub end: u16,
+    pub mode: MergeMode,
+    /// Priority of Blaulicht's own frames, compared against the sACN priority
+    /// of the external source in [`MergeMode::Priority`].
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+}
+
+#[derive(Default)]
+pub struct Merger {
+    ranges: Vec<MergeRange>,
+    last_internal: HashMap<u16, Frame>,
+    last_external: HashMap<u16, Frame>,
+    /// Per channel: whether the external console changed it more recently than Blaulicht.
+    external_latest: HashMap<u16, [bool; UNIVERSE_SIZE]>,
+}
+
+impl Merger {
+    pub fn set_ranges(&mut self, ranges: Vec<MergeRange>) {
+        self.ranges = ranges
+            .into_iter()
+            .filter(|r| r.start >= 1 && r.start <= r.end)
+            .map(|r| MergeRange {
+                end: r.end.min(UNIVERSE_SIZE as u16),
+                ..r
+            })
+            .collect();
+    }
+
+    pub fn ranges(&self) -> &[MergeRange] {
+        &self.ranges
+    }
+
+    /// Merges the external frames into Blaulicht's frames, in place.
+    /// Channels taken over by the external console are marked in `owners`.
+    pub fn merge(
+        &mut self,
+        frames: &mut Vec<Frame>,
+        owners: &mut Vec<Owners>,
+        network: &NetworkInputs,
+    ) {
+        let universes: HashSet<u16> = self.ranges.iter().map(|r| r.universe).collect();
+
+        for universe in universes {
+            let Some(external) = network.frame(universe) else {
+                continue;
+            };
+
+            let index = universe as usize;
+            if frames.len() <= index {
+                frames.resize(index + 1, [0; UNIVERSE_SIZE]);
+            }
+            if owners.len() <= index {
+                owners.resize(index + 1, [Owner::Engine; UNIVERSE_SIZE]);
+            }
+
+            let internal = frames[index];
+            let last_internal = self
+                .last_internal
+                .insert(universe, internal)
+                .unwrap_or(internal);
+            let last_external = self
+                .last_external
+                .insert(universe, external.data)
+                .unwrap_or(external.data);
+
+            let external_latest = self
+                .external_latest
+                .entry(universe)
+                .or_insert([false; UNIVERSE_SIZE]);
+            for channel in 0..UNIVERSE_SIZE {
+                // Blaulicht wins if both sides changed a channel at the same time.
+                if internal[channel] != last_internal[channel] {
+                    external_latest[channel] = false;
+                } else if external.data[channel] != last_external[channel] {
+                    external_latest[channel] = true;
+                }
+            }
+
+            for range in self.ranges.iter().filter(|r| r.universe == universe) {
+                for channel in (range.start - 1) as usize..range.end as usize {
+                    let ours = internal[channel];
+                    let theirs = external.data[channel];
+
+                    let theirs_wins = match range.mode {
+                        MergeMode::Htp => theirs > ours,
+                        MergeMode::Ltp => external_latest[channel],
+                        MergeMode::Priority if external.priority != range.priority => {
+                            external.priority > range.priority
+                        }
+                        MergeMode::Priority => theirs > ours,
+                    };
+
+                    if theirs_wins {
+                        frames[index][channel] = theirs;
+                        owners[index][channel] = external.protocol.into();
+                    }
+                }
+            }
+        }
+    }
+}
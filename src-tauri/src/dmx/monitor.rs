@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use super::{EffectOwners, Frame, Owner, Owners, UNIVERSE_SIZE};
+
+/// The frontend does not need more than 10 updates per second to be readable.
+pub const PUBLISH_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelUpdate {
+    /// Channel number, starting at 1.
+    pub channel: u16,
+    pub value: u8,
+    pub owner: Owner,
+    /// The effect and layer that wrote an engine channel.
+    pub effect: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UniverseUpdate {
+    pub universe: u16,
+    /// If set, `channels` contains every channel instead of only the changed ones.
+    pub full: bool,
+    pub channels: Vec<ChannelUpdate>,
+}
+
+/// Turns the output frames into throttled diffs for the frontend.
+pub struct Monitor {
+    sent: Vec<(Frame, Owners, Vec<Option<String>>)>,
+    time_of_last_publish: Instant,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Monitor {
+    pub fn new() -> Self {
+        Self {
+            sent: vec![],
+            time_of_last_publish: Instant::now(),
+        }
+    }
+
+    /// Sends every channel of every universe with the next update.
+    pub fn resync(&mut self) {
+        self.sent.clear();
+    }
+
+    /// Returns the changes since the last publish, or nothing if it is not time to publish yet.
+    pub fn update(
+        &mut self,
+        frames: &[Frame],
+        owners: &[Owners],
+        effects: &EffectOwners,
+    ) -> Vec<UniverseUpdate> {
+        if self.time_of_last_publish.elapsed() < PUBLISH_INTERVAL {
+            return vec![];
+        }
+        self.time_of_last_publish = Instant::now();
+
+        let mut updates = vec![];
+
+        for (universe, frame) in frames.iter().enumerate() {
+            let owners = owners
+                .get(universe)
+                .copied()
+                .unwrap_or([Owner::Engine; UNIVERSE_SIZE]);
+            // Only the channels the engine still owns after merging show its effects.
+            let effects: Vec<Option<String>> = (0..UNIVERSE_SIZE)
+                .map(|i| match owners[i] {
+                    Owner::Engine => effects.name(universe, i).map(str::to_string),
+                    _ => None,
+                })
+                .collect();
+
+            let previous = self.sent.get(universe);
+            let channels: Vec<ChannelUpdate> = (0..UNIVERSE_SIZE)
+                .filter(|&i| match previous {
+                    Some((sent_frame, sent_owners, sent_effects)) => {
+                        sent_frame[i] != frame[i]
+                            || sent_owners[i] != owners[i]
+                            || sent_effects[i] != effects[i]
+                    }
+                    None => true,
+                })
+                .map(|i| ChannelUpdate {
+                    channel: i as u16 + 1,
+                    value: frame[i],
+                    owner: owners[i],
+                    effect: effects[i].clone(),
+                })
+                .collect();
+
+            if channels.is_empty() {
+                continue;
+            }
+
+            updates.push(UniverseUpdate {
+                universe: universe as u16,
+                full: previous.is_none(),
+                channels,
+            });
+
+            if universe < self.sent.len() {
+                self.sent[universe] = (*frame, owners, effects);
+            } else {
+                self.sent.push((*frame, owners, effects));
+            }
+        }
+
+        updates
+    }
+}
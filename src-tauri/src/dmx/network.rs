@@ -0,0 +1,334 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{Ipv4Addr, UdpSocket},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Frame, Owner, UNIVERSE_SIZE};
+
+pub const ARTNET_PORT: u16 = 6454;
+pub const SACN_PORT: u16 = 5568;
+
+/// sACN considers a source lost after 2.5s without data, Art-Net does not define one.
+const SOURCE_TIMEOUT: Duration = Duration::from_millis(2500);
+/// Art-Net has no notion of priority, its data is treated like a default sACN source.
+const DEFAULT_PRIORITY: u8 = 100;
+const RECEIVE_TIMEOUT: Duration = Duration::from_millis(100);
+
+const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+const ARTNET_OP_DMX: u16 = 0x5000;
+const ARTNET_DATA_OFFSET: usize = 18;
+
+const SACN_PACKET_IDENTIFIER: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const SACN_VECTOR_ROOT_DATA: u32 = 0x0000_0004;
+const SACN_VECTOR_FRAMING_DATA: u32 = 0x0000_0002;
+const SACN_OPTION_PREVIEW: u8 = 0x80;
+const SACN_OPTION_TERMINATED: u8 = 0x40;
+/// Offset of the DMX start code, slot data follows directly.
+const SACN_START_CODE_OFFSET: usize = 125;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputProtocol {
+    ArtNet,
+    Sacn,
+}
+
+impl From<InputProtocol> for Owner {
+    fn from(protocol: InputProtocol) -> Self {
+        match protocol {
+            InputProtocol::ArtNet => Owner::ArtNet,
+            InputProtocol::Sacn => Owner::Sacn,
+        }
+    }
+}
+
+/// Maps a universe received from the network onto one of Blaulicht's universes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInput {
+    pub protocol: InputProtocol,
+    /// Art-Net port address (starting at 0) or sACN universe (starting at 1).
+    pub external_universe: u16,
+    /// Blaulicht universe the received frames are merged into.
+    pub universe: u16,
+}
+
+#[derive(Clone, Copy)]
+pub struct ExternalFrame {
+    pub data: Frame,
+    pub priority: u8,
+    pub protocol: InputProtocol,
+    received: Instant,
+}
+
+/// Latest frames received from an external console, per Blaulicht universe.
+#[derive(Default)]
+pub struct NetworkInputs {
+    inputs: Mutex<Vec<NetworkInput>>,
+    frames: Mutex<HashMap<u16, ExternalFrame>>,
+}
+
+impl NetworkInputs {
+    pub fn set_inputs(&self, inputs: Vec<NetworkInput>) {
+        *self.inputs.lock().unwrap() = inputs;
+        self.frames.lock().unwrap().clear();
+    }
+
+    pub fn inputs(&self) -> Vec<NetworkInput> {
+        self.inputs.lock().unwrap().clone()
+    }
+
+    /// Returns the received frame of the given universe, unless its source went silent.
+    pub fn frame(&self, universe: u16) -> Option<ExternalFrame> {
+        let frames = self.frames.lock().unwrap();
+        frames
+            .get(&universe)
+            .filter(|f| f.received.elapsed() < SOURCE_TIMEOUT)
+            .copied()
+    }
+
+    fn receive(&self, protocol: InputProtocol, external_universe: u16, data: &[u8], priority: u8) {
+        let inputs = self.inputs.lock().unwrap();
+        let mut frames = self.frames.lock().unwrap();
+
+        for input in inputs
+            .iter()
+            .filter(|i| i.protocol == protocol && i.external_universe == external_universe)
+        {
+            // A higher priority source feeding the same universe keeps it until it goes silent.
+            if let Some(existing) = frames.get(&input.universe) {
+                if existing.priority > priority && existing.received.elapsed() < SOURCE_TIMEOUT {
+                    continue;
+                }
+            }
+
+            let mut frame = [0; UNIVERSE_SIZE];
+            let len = data.len().min(UNIVERSE_SIZE);
+            frame[..len].copy_from_slice(&data[..len]);
+
+            frames.insert(
+                input.universe,
+                ExternalFrame {
+                    data: frame,
+                    priority,
+                    protocol,
+                    received: Instant::now(),
+                },
+            );
+        }
+    }
+
+    /// Takes the slot data of an ArtDmx packet. Returns false for anything else.
+    pub fn receive_artnet(&self, packet: &[u8]) -> bool {
+        let Some((universe, data)) = parse_artnet(packet) else {
+            return false;
+        };
+        self.receive(InputProtocol::ArtNet, universe, data, DEFAULT_PRIORITY);
+        true
+    }
+
+    /// Takes the slot data of an E1.31 data packet, or forgets the source of a
+    /// terminated stream. Returns false for anything else, e.g. preview data.
+    pub fn receive_sacn(&self, packet: &[u8]) -> bool {
+        match parse_sacn(packet) {
+            Some(SacnPacket::Data {
+                universe,
+                priority,
+                data,
+            }) => self.receive(InputProtocol::Sacn, universe, data, priority),
+            Some(SacnPacket::Terminated { universe }) => {
+                self.terminate(InputProtocol::Sacn, universe)
+            }
+            None => return false,
+        }
+        true
+    }
+
+    fn terminate(&self, protocol: InputProtocol, external_universe: u16) {
+        let inputs = self.inputs.lock().unwrap();
+        let mut frames = self.frames.lock().unwrap();
+
+        for input in inputs
+            .iter()
+            .filter(|i| i.protocol == protocol && i.external_universe == external_universe)
+        {
+            frames.remove(&input.universe);
+        }
+    }
+}
+
+/// Returns the port address and slot data of an ArtDmx packet.
+fn parse_artnet(packet: &[u8]) -> Option<(u16, &[u8])> {
+    if packet.len() < ARTNET_DATA_OFFSET || &packet[..8] != ARTNET_ID {
+        return None;
+    }
+
+    let opcode = u16::from_le_bytes([packet[8], packet[9]]);
+    if opcode != ARTNET_OP_DMX {
+        return None;
+    }
+
+    let sub_uni = packet[14] as u16;
+    let net = (packet[15] & 0x7F) as u16;
+    let universe = (net << 8) | sub_uni;
+
+    let len = u16::from_be_bytes([packet[16], packet[17]]) as usize;
+    let data = packet.get(ARTNET_DATA_OFFSET..ARTNET_DATA_OFFSET + len)?;
+
+    Some((universe, data))
+}
+
+enum SacnPacket<'a> {
+    Data {
+        universe: u16,
+        priority: u8,
+        data: &'a [u8],
+    },
+    Terminated {
+        universe: u16,
+    },
+}
+
+fn parse_sacn(packet: &[u8]) -> Option<SacnPacket<'_>> {
+    if packet.len() < SACN_START_CODE_OFFSET + 1 || &packet[4..16] != SACN_PACKET_IDENTIFIER {
+        return None;
+    }
+
+    let root_vector = u32::from_be_bytes(packet[18..22].try_into().ok()?);
+    let framing_vector = u32::from_be_bytes(packet[40..44].try_into().ok()?);
+    if root_vector != SACN_VECTOR_ROOT_DATA || framing_vector != SACN_VECTOR_FRAMING_DATA {
+        return None;
+    }
+
+    let priority = packet[108];
+    let options = packet[112];
+    let universe = u16::from_be_bytes([packet[113], packet[114]]);
+
+    if options & SACN_OPTION_TERMINATED != 0 {
+        return Some(SacnPacket::Terminated { universe });
+    }
+
+    // Only null start code frames carry dimmer data, and preview data is not meant for output.
+    if options & SACN_OPTION_PREVIEW != 0 || packet[SACN_START_CODE_OFFSET] != 0 {
+        return None;
+    }
+
+    // The property value count includes the start code.
+    let count = u16::from_be_bytes([packet[123], packet[124]]) as usize;
+    let data = packet.get(SACN_START_CODE_OFFSET + 1..SACN_START_CODE_OFFSET + count)?;
+
+    Some(SacnPacket::Data {
+        universe,
+        priority,
+        data,
+    })
+}
+
+fn bind(port: u16) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))?;
+    socket.set_read_timeout(Some(RECEIVE_TIMEOUT))?;
+    Ok(socket)
+}
+
+fn sacn_multicast_group(universe: u16) -> Ipv4Addr {
+    let [hi, lo] = universe.to_be_bytes();
+    Ipv4Addr::new(239, 255, hi, lo)
+}
+
+fn artnet_thread(network: Arc<NetworkInputs>) {
+    let socket = match bind(ARTNET_PORT) {
+        Ok(socket) => socket,
+        Err(err) => {
+            eprintln!("[dmx] Art-Net input unavailable: {err}");
+            return;
+        }
+    };
+    println!("[dmx] Listening for Art-Net on port {ARTNET_PORT}");
+
+    let mut buf = [0; 1024];
+    loop {
+        let len = match socket.recv(&mut buf) {
+            Ok(len) => len,
+            Err(err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                continue
+            }
+            Err(err) => {
+                eprintln!("[dmx] Art-Net input failed: {err}");
+                return;
+            }
+        };
+
+        network.receive_artnet(&buf[..len]);
+    }
+}
+
+fn sacn_thread(network: Arc<NetworkInputs>) {
+    let socket = match bind(SACN_PORT) {
+        Ok(socket) => socket,
+        Err(err) => {
+            eprintln!("[dmx] sACN input unavailable: {err}");
+            return;
+        }
+    };
+    println!("[dmx] Listening for sACN on port {SACN_PORT}");
+
+    let mut joined: Vec<u16> = vec![];
+    let mut buf = [0; 1024];
+    loop {
+        // sACN is multicast, we have to subscribe to every configured universe.
+        let mut wanted: Vec<u16> = network
+            .inputs()
+            .iter()
+            .filter(|i| i.protocol == InputProtocol::Sacn)
+            .map(|i| i.external_universe)
+            .collect();
+        wanted.sort_unstable();
+        wanted.dedup();
+
+        if wanted != joined {
+            for universe in joined.iter().filter(|u| !wanted.contains(u)) {
+                let _ = socket
+                    .leave_multicast_v4(&sacn_multicast_group(*universe), &Ipv4Addr::UNSPECIFIED);
+            }
+            for universe in wanted.iter().filter(|u| !joined.contains(u)) {
+                if let Err(err) = socket
+                    .join_multicast_v4(&sacn_multicast_group(*universe), &Ipv4Addr::UNSPECIFIED)
+                {
+                    eprintln!("[dmx] Failed to join sACN universe {universe}: {err}");
+                }
+            }
+            joined = wanted;
+        }
+
+        let len = match socket.recv(&mut buf) {
+            Ok(len) => len,
+            Err(err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                continue
+            }
+            Err(err) => {
+                eprintln!("[dmx] sACN input failed: {err}");
+                return;
+            }
+        };
+
+        network.receive_sacn(&buf[..len]);
+    }
+}
+
+/// Spawns the Art-Net and sACN receiver threads.
+pub fn spawn(network: Arc<NetworkInputs>) {
+    let artnet = network.clone();
+    thread::spawn(move || artnet_thread(artnet));
+    thread::spawn(move || sacn_thread(network));
+}
@@ -0,0 +1,649 @@
+use std::{
+    io,
+    path::PathBuf,
+    sync::{
+        mpsc::{Receiver, Sender, TryRecvError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use serialport::SerialPortType;
+
+use crate::{
+    audio::SystemMessage,
+    worker::{self, Ticker},
+};
+
+use super::{
+    interfaces::{self, DmxTiming, InterfaceDefinition},
+    manual::ManualOverrides,
+    master::{MasterState, Masters, PanicMode},
+    merge::{MergeRange, Merger},
+    monitor::Monitor,
+    network::{NetworkInput, NetworkInputs},
+    recording::{Player, Recorder},
+    walk::{Walk, WalkConfig},
+    DmxUniverse, EffectOwners, Frame, Owner, UNIVERSE_SIZE,
+};
+
+/// A full 512 slot frame takes ~22.7ms on the wire, so refreshing faster is pointless.
+pub const MAX_REFRESH_RATE_HZ: f32 = 44.0;
+pub const MIN_REFRESH_RATE_HZ: f32 = 1.0;
+pub const DEFAULT_REFRESH_RATE_HZ: f32 = 40.0;
+
+const FRAME_RATE_REPORT_INTERVAL: Duration = Duration::from_millis(1000);
+/// How long a lost output waits between looking for its interface.
+pub const RECONNECT_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Double-buffered universe frames.
+///
+/// The engine modifies the back buffer at its own pace and calls [`FrameBuffer::publish`]
+/// once a consistent state has been reached. The output thread only ever reads the front
+/// buffer, so it never sends a half-updated frame.
+pub struct FrameBuffer {
+    back: Mutex<Vec<Frame>>,
+    front: Mutex<Vec<Frame>>,
+    back_effects: Mutex<EffectOwners>,
+    front_effects: Mutex<EffectOwners>,
+}
+
+impl FrameBuffer {
+    pub fn new(universes: usize) -> Self {
+        Self {
+            back: Mutex::new(vec![[0; UNIVERSE_SIZE]; universes]),
+            front: Mutex::new(vec![[0; UNIVERSE_SIZE]; universes]),
+            back_effects: Mutex::default(),
+            front_effects: Mutex::default(),
+        }
+    }
+
+    /// Modifies the back buffer. Changes become visible to outputs after [`FrameBuffer::publish`].
+    pub fn write<T>(&self, f: impl FnOnce(&mut Vec<Frame>) -> T) -> T {
+        let mut back = self.back.lock().unwrap();
+        f(&mut back)
+    }
+
+    /// Modifies the effects that wrote the channels of the back buffer, published with it.
+    pub fn write_effects<T>(&self, f: impl FnOnce(&mut EffectOwners) -> T) -> T {
+        let mut back = self.back_effects.lock().unwrap();
+        f(&mut back)
+    }
+
+    pub fn publish(&self) {
+        let back = self.back.lock().unwrap();
+        let mut front = self.front.lock().unwrap();
+        front.clone_from(&back);
+
+        let back = self.back_effects.lock().unwrap();
+        let mut front = self.front_effects.lock().unwrap();
+        front.clone_from(&back);
+    }
+
+    /// Returns a copy of all published frames.
+    pub fn snapshot(&self) -> Vec<Frame> {
+        self.front.lock().unwrap().clone()
+    }
+
+    /// Returns a copy of the effects that wrote the published frames.
+    pub fn snapshot_effects(&self) -> EffectOwners {
+        self.front_effects.lock().unwrap().clone()
+    }
+
+    /// Returns a copy of the published frame of the given universe.
+    pub fn read(&self, universe: u16) -> Frame {
+        let front = self.front.lock().unwrap();
+        front
+            .get(universe as usize)
+            .copied()
+            .unwrap_or([0; UNIVERSE_SIZE])
+    }
+}
+
+pub enum OutputCommand {
+    /// Clamped to [`MIN_REFRESH_RATE_HZ`]..=[`MAX_REFRESH_RATE_HZ`], ignored if not finite.
+    SetRefreshRate(f32),
+    /// Output the given universe on an explicitly chosen serial port.
+    SelectPort {
+        universe: u16,
+        port: String,
+    },
+    /// Overrides the interface's default timing for the output of the given universe.
+    SetTiming {
+        universe: u16,
+        timing: DmxTiming,
+    },
+    /// Records the frames of every universe to the given file.
+    StartRecording(PathBuf),
+    StopRecording,
+    /// Sends a recording to the outputs instead of the engine's frames.
+    StartPlayback {
+        path: PathBuf,
+        looped: bool,
+    },
+    StopPlayback,
+    SetNetworkInputs(Vec<NetworkInput>),
+    /// Replaces all merge ranges between the external console and Blaulicht.
+    SetMergeRanges(Vec<MergeRange>),
+    /// Sends all channels to the frontend with the next monitor update, not just the changes.
+    ResyncMonitor,
+    /// Overrides consecutive channels beginning at `start` (starting at 1) until released.
+    SetChannels {
+        universe: u16,
+        start: u16,
+        values: Vec<u8>,
+    },
+    /// Replies with the values of `count` channels beginning at `start` as last sent out.
+    GetChannels {
+        universe: u16,
+        start: u16,
+        count: u16,
+        reply: Sender<Vec<u8>>,
+    },
+    ReleaseChannels {
+        universe: u16,
+        start: u16,
+        count: u16,
+    },
+    ReleaseAllChannels,
+    /// Scales intensity channels, 0.0 to 1.0.
+    SetGrandMaster(f32),
+    SetBlackout(bool),
+    Panic(PanicMode),
+    ReleasePanic,
+    /// Declares which channels (starting at 1) of a universe are dimmer or intensity channels.
+    SetIntensityChannels {
+        universe: u16,
+        channels: Vec<u16>,
+    },
+    GetMasters(Sender<MasterState>),
+    /// Replaces the walked universe with a channel walk until stopped.
+    StartWalk(WalkConfig),
+    StopWalk,
+    /// Steps the running walk by hand, forwards or backwards.
+    StepWalk {
+        forward: bool,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputStatus {
+    Connected,
+    Disconnected,
+}
+
+/// Where to look for an output's port once it went away.
+enum Reconnect {
+    /// The first of these interfaces to show up, under whatever port name it gets.
+    Interfaces(Vec<InterfaceDefinition>),
+    /// The same port name again, driven with the given interface definition.
+    Port(InterfaceDefinition),
+}
+
+struct Output {
+    universe: u16,
+    port_name: String,
+    reconnect_to: Reconnect,
+    timing: Option<DmxTiming>,
+    serial: Option<DmxUniverse>,
+    time_of_last_reconnect: Instant,
+}
+
+impl Output {
+    fn new(universe: u16, port_name: String, reconnect_to: Reconnect) -> Self {
+        Self {
+            universe,
+            port_name,
+            reconnect_to,
+            timing: None,
+            serial: None,
+            time_of_last_reconnect: Instant::now(),
+        }
+    }
+
+    fn open(&mut self, definition: InterfaceDefinition, system_out: &Sender<SystemMessage>) {
+        let mut serial = match DmxUniverse::open(&self.port_name, definition) {
+            Ok(serial) => serial,
+            Err(err) => {
+                eprintln!("[dmx] Failed to open {}: {err}", self.port_name);
+                return;
+            }
+        };
+
+        if let Some(timing) = self.timing {
+            if let Err(err) = serial.set_timing(timing) {
+                eprintln!("[dmx] Failed to set timing on {}: {err}", self.port_name);
+                return;
+            }
+        }
+
+        println!("[dmx] Universe {} on {}", self.universe, self.port_name);
+        self.serial = Some(serial);
+        self.report(OutputStatus::Connected, system_out);
+    }
+
+    fn disconnect(&mut self, err: io::Error, system_out: &Sender<SystemMessage>) {
+        eprintln!("[dmx] Lost {}: {err}", self.port_name);
+        self.serial = None;
+        self.time_of_last_reconnect = Instant::now();
+        self.report(OutputStatus::Disconnected, system_out);
+    }
+
+    /// Looks for the interface again, it may have been re-enumerated under a different port name.
+    fn reconnect(&mut self, system_out: &Sender<SystemMessage>) {
+        self.time_of_last_reconnect = Instant::now();
+
+        let found = match &self.reconnect_to {
+            Reconnect::Interfaces(definitions) => interfaces::find_port(definitions)
+                .map(|(port, definition)| (port.port_name, definition)),
+            Reconnect::Port(definition) => serialport::available_ports()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|p| p.port_name == self.port_name)
+                .map(|port| (port.port_name, definition.clone())),
+        };
+
+        if let Some((port_name, definition)) = found {
+            self.port_name = port_name;
+            self.open(definition, system_out);
+        }
+    }
+
+    fn report(&self, status: OutputStatus, system_out: &Sender<SystemMessage>) {
+        notify(
+            system_out,
+            SystemMessage::DmxOutputStatus {
+                universe: self.universe,
+                status,
+            },
+        );
+    }
+}
+
+/// Whether a failed frame write means that the port is gone, and is to be looked for
+/// again, see [`reconnect_due`]. A timeout on a slow interface only costs a frame, the
+/// next one is sent as usual.
+pub fn port_lost(err: &io::Error) -> bool {
+    err.kind() != io::ErrorKind::TimedOut
+}
+
+/// Whether a lost output should look for its interface again, given when it last tried
+/// or lost the port. Trying every tick would stall the output on slow port enumeration.
+pub fn reconnect_due(time_of_last_attempt: Instant, now: Instant) -> bool {
+    now.saturating_duration_since(time_of_last_attempt) > RECONNECT_INTERVAL
+}
+
+/// Sends a message towards the frontend. DMX output goes on without anyone listening,
+/// e.g. while the audio thread restarts with another device.
+fn notify(system_out: &Sender<SystemMessage>, message: SystemMessage) {
+    let _ = system_out.send(message);
+}
+
+/// Creates an output for an explicitly selected port.
+fn select_port(
+    definitions: &[InterfaceDefinition],
+    universe: u16,
+    port_name: String,
+) -> Option<(Output, InterfaceDefinition)> {
+    let ports = serialport::available_ports().unwrap_or_default();
+    let port = ports.iter().find(|p| p.port_name == port_name);
+
+    // Pin the reconnect target to this exact device, so that we do not jump
+    // to another identical interface when the cable is unplugged.
+    let known = port.and_then(|port| {
+        let SerialPortType::UsbPort(usb) = &port.port_type else {
+            return None;
+        };
+
+        let mut definition = interfaces::definition_for(definitions, port)?.clone();
+        if definition.serial_number.is_none() {
+            definition.serial_number = usb.serial_number.clone();
+        }
+        Some(definition)
+    });
+
+    match known {
+        Some(definition) => Some((
+            Output::new(
+                universe,
+                port_name,
+                Reconnect::Interfaces(vec![definition.clone()]),
+            ),
+            definition,
+        )),
+        // Unknown ports are driven like the first registered interface.
+        None => match definitions.first() {
+            Some(definition) => Some((
+                Output::new(universe, port_name, Reconnect::Port(definition.clone())),
+                definition.clone(),
+            )),
+            None => {
+                println!("[dmx] Cannot use port {port_name}: interface registry is empty");
+                None
+            }
+        },
+    }
+}
+
+struct OutputThread {
+    definitions: Vec<InterfaceDefinition>,
+    frames: Arc<FrameBuffer>,
+    network: Arc<NetworkInputs>,
+    merger: Merger,
+    manual: ManualOverrides,
+    masters: Masters,
+    walk: Option<Walk>,
+    monitor: Monitor,
+    /// Frames as sent out by the last tick.
+    sent: Vec<Frame>,
+    system_out: Sender<SystemMessage>,
+    outputs: Vec<Output>,
+    period: Duration,
+    recorder: Option<Recorder>,
+    player: Option<Player>,
+}
+
+impl OutputThread {
+    fn handle(&mut self, command: OutputCommand) {
+        match command {
+            // Clamping lets NaN through, which no period can be derived from.
+            OutputCommand::SetRefreshRate(hz) if !hz.is_finite() => {
+                eprintln!("[dmx] Ignoring refresh rate {hz}");
+            }
+            OutputCommand::SetRefreshRate(hz) => {
+                let hz = hz.clamp(MIN_REFRESH_RATE_HZ, MAX_REFRESH_RATE_HZ);
+                println!("[dmx] Refresh rate: {hz}Hz");
+                self.period = Duration::from_secs_f32(1.0 / hz);
+            }
+            OutputCommand::SelectPort { universe, port } => {
+                if let Some((mut output, definition)) =
+                    select_port(&self.definitions, universe, port)
+                {
+                    // Release the old port before opening the new one, it might be the same.
+                    self.outputs.retain(|o| o.universe != universe);
+                    output.open(definition, &self.system_out);
+                    self.outputs.push(output);
+                }
+            }
+            OutputCommand::SetTiming { universe, timing } => {
+                for output in self.outputs.iter_mut().filter(|o| o.universe == universe) {
+                    output.timing = Some(timing);
+
+                    let Some(serial) = &mut output.serial else {
+                        continue;
+                    };
+
+                    match serial.set_timing(timing) {
+                        Ok(()) => {
+                            println!("[dmx] Universe {universe} timing: {:?}", serial.timing())
+                        }
+                        Err(err) => output.disconnect(err, &self.system_out),
+                    }
+                }
+            }
+            OutputCommand::StartRecording(path) => match Recorder::create(&path) {
+                Ok(recorder) => {
+                    println!("[dmx] Recording to {}", path.display());
+                    self.stop_recording();
+                    self.recorder = Some(recorder);
+                }
+                Err(err) => eprintln!("[dmx] Failed to record to {}: {err}", path.display()),
+            },
+            OutputCommand::StopRecording => self.stop_recording(),
+            OutputCommand::StartPlayback { path, looped } => match Player::open(&path, looped) {
+                Ok(player) => {
+                    println!("[dmx] Playing {}", path.display());
+                    self.player = Some(player);
+                }
+                Err(err) => eprintln!("[dmx] Failed to play {}: {err}", path.display()),
+            },
+            OutputCommand::StopPlayback => self.player = None,
+            OutputCommand::SetNetworkInputs(inputs) => self.network.set_inputs(inputs),
+            OutputCommand::SetMergeRanges(ranges) => {
+                self.merger.set_ranges(ranges);
+                println!("[dmx] Merge ranges: {:?}", self.merger.ranges());
+            }
+            OutputCommand::ResyncMonitor => self.monitor.resync(),
+            OutputCommand::SetChannels {
+                universe,
+                start,
+                values,
+            } => self.manual.set(universe, start, &values),
+            OutputCommand::GetChannels {
+                universe,
+                start,
+                count,
+                reply,
+            } => {
+                let frame = self
+                    .sent
+                    .get(universe as usize)
+                    .unwrap_or(&[0; UNIVERSE_SIZE]);
+                let start = start.max(1) as usize - 1;
+                let values = frame.iter().skip(start).take(count as usize).copied();
+                worker::reply(reply, values.collect());
+            }
+            OutputCommand::ReleaseChannels {
+                universe,
+                start,
+                count,
+            } => self.manual.release(universe, start, count),
+            OutputCommand::ReleaseAllChannels => self.manual.release_all(),
+            OutputCommand::SetGrandMaster(level) => {
+                self.masters.set_grand_master(level);
+                self.report_masters();
+            }
+            OutputCommand::SetBlackout(blackout) => {
+                println!("[dmx] Blackout: {blackout}");
+                self.masters.set_blackout(blackout);
+                self.report_masters();
+            }
+            OutputCommand::Panic(mode) => {
+                println!("[dmx] Panic: {mode:?}");
+                self.masters.panic(mode, &self.sent);
+                self.report_masters();
+            }
+            OutputCommand::ReleasePanic => {
+                println!("[dmx] Panic released");
+                self.masters.release_panic();
+                self.report_masters();
+            }
+            OutputCommand::SetIntensityChannels { universe, channels } => {
+                self.masters.set_intensity_channels(universe, channels)
+            }
+            OutputCommand::GetMasters(reply) => {
+                worker::reply(reply, self.masters.state());
+            }
+            OutputCommand::StartWalk(config) => {
+                println!("[dmx] Channel walk: {config:?}");
+                self.walk = Some(Walk::new(config));
+                self.report_walk();
+            }
+            OutputCommand::StopWalk => {
+                println!("[dmx] Channel walk stopped");
+                self.walk = None;
+                self.report_walk();
+            }
+            OutputCommand::StepWalk { forward } => {
+                if let Some(walk) = &mut self.walk {
+                    walk.step(forward);
+                    self.report_walk();
+                }
+            }
+        }
+    }
+
+    fn report_walk(&self) {
+        notify(
+            &self.system_out,
+            SystemMessage::Walk(self.walk.as_ref().map(Walk::state)),
+        );
+    }
+
+    fn report_masters(&self) {
+        notify(
+            &self.system_out,
+            SystemMessage::Masters(self.masters.state()),
+        );
+    }
+
+    fn stop_recording(&mut self) {
+        let Some(recorder) = self.recorder.take() else {
+            return;
+        };
+
+        match recorder.finish() {
+            Ok(()) => println!("[dmx] Recording stopped"),
+            Err(err) => eprintln!("[dmx] Failed to finish recording: {err}"),
+        }
+    }
+
+    /// Returns the frames to be sent: a running playback replaces the engine's frames.
+    /// Along with the engine's frames come the effects that wrote them.
+    fn source(&mut self) -> (Vec<Frame>, Owner, EffectOwners) {
+        if self.player.as_ref().is_some_and(|p| p.finished()) {
+            println!("[dmx] Playback finished");
+            self.player = None;
+        }
+
+        if let Some(player) = &mut self.player {
+            match player.advance() {
+                Ok(frames) => return (frames.to_vec(), Owner::Playback, EffectOwners::default()),
+                Err(err) => eprintln!("[dmx] Playback failed: {err}"),
+            }
+            self.player = None;
+        }
+
+        (
+            self.frames.snapshot(),
+            Owner::Engine,
+            self.frames.snapshot_effects(),
+        )
+    }
+
+    fn tick(&mut self) {
+        let (mut frames, owner, effects) = self.source();
+        let mut owners = vec![[owner; UNIVERSE_SIZE]; frames.len()];
+        self.merger.merge(&mut frames, &mut owners, &self.network);
+        self.manual.apply(&mut frames, &mut owners);
+        if let Some(walk) = &mut self.walk {
+            if walk.advance() {
+                self.report_walk();
+            }
+        }
+        if let Some(walk) = &self.walk {
+            walk.apply(&mut frames, &mut owners);
+        }
+        self.masters.apply(&mut frames, &mut owners);
+
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(err) = recorder.record(&frames) {
+                eprintln!("[dmx] Recording failed: {err}");
+                self.recorder = None;
+            }
+        }
+
+        for output in &mut self.outputs {
+            let Some(serial) = &mut output.serial else {
+                if reconnect_due(output.time_of_last_reconnect, Instant::now()) {
+                    output.reconnect(&self.system_out);
+                }
+                continue;
+            };
+
+            let frame = frames
+                .get(output.universe as usize)
+                .unwrap_or(&[0; UNIVERSE_SIZE]);
+            serial.set_frame(frame);
+            match serial.write_to_serial() {
+                Ok(()) => {}
+                Err(err) if !port_lost(&err) => {
+                    eprintln!("[dmx] Frame to {} timed out", output.port_name);
+                }
+                Err(err) => output.disconnect(err, &self.system_out),
+            }
+        }
+
+        for update in self.monitor.update(&frames, &owners, &effects) {
+            notify(&self.system_out, SystemMessage::Universe(update));
+        }
+
+        self.sent = frames;
+    }
+}
+
+/// Spawns the thread refreshing every output at a fixed rate, independent of the engine.
+///
+/// Outputs whose port fails are reported as [`OutputStatus::Disconnected`] and reopened
+/// as soon as a matching interface shows up again.
+pub fn spawn(
+    interfaces_path: PathBuf,
+    frames: Arc<FrameBuffer>,
+    network: Arc<NetworkInputs>,
+    commands: Receiver<OutputCommand>,
+    system_out: Sender<SystemMessage>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let definitions = interfaces::load(&interfaces_path);
+
+        // Universe 0 goes to whatever registered interface is plugged in first.
+        let mut output = Output::new(0, String::new(), Reconnect::Interfaces(definitions.clone()));
+        output.reconnect(&system_out);
+        if output.serial.is_none() {
+            println!("[dmx] No registered DMX interface found");
+            output.report(OutputStatus::Disconnected, &system_out);
+        }
+
+        let mut thread = OutputThread {
+            definitions,
+            frames,
+            network,
+            merger: Merger::default(),
+            manual: ManualOverrides::default(),
+            masters: Masters::default(),
+            walk: None,
+            monitor: Monitor::new(),
+            sent: vec![],
+            system_out,
+            outputs: vec![output],
+            period: Duration::from_secs_f32(1.0 / DEFAULT_REFRESH_RATE_HZ),
+            recorder: None,
+            player: None,
+        };
+
+        let mut ticker = Ticker::default();
+
+        let mut frames_since_report = 0;
+        let mut time_of_last_report = Instant::now();
+
+        loop {
+            loop {
+                match commands.try_recv() {
+                    Ok(command) => thread.handle(command),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        println!("[dmx] Output thread exiting");
+                        thread.stop_recording();
+                        return;
+                    }
+                }
+            }
+
+            thread.tick();
+
+            frames_since_report += 1;
+            let now = Instant::now();
+            if now - time_of_last_report > FRAME_RATE_REPORT_INTERVAL {
+                let rate = frames_since_report as f32 / (now - time_of_last_report).as_secs_f32();
+                notify(&thread.system_out, SystemMessage::DmxFrameRate(rate));
+                frames_since_report = 0;
+                time_of_last_report = now;
+            }
+
+            ticker.wait(thread.period);
+        }
+    })
+}
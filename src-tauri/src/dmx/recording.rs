@@ -0,0 +1,248 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use super::{Frame, UNIVERSE_SIZE};
+
+//
+// File format (all integers little endian):
+//
+//   header: b"BLDMX" <version: u8>
+//   record: <timestamp_ms: u32> <universe: u16> <kind: u8> <payload>
+//
+// A record of kind `RECORD_FULL` carries all 512 slots, a record of kind `RECORD_DELTA`
+// carries <count: u16> followed by `count` times <channel index: u16> <value: u8>.
+// Records are only written if the universe changed since the previous frame.
+//
+
+const MAGIC: &[u8; 5] = b"BLDMX";
+const VERSION: u8 = 1;
+const HEADER_LEN: u64 = MAGIC.len() as u64 + 1;
+
+const RECORD_FULL: u8 = 0;
+const RECORD_DELTA: u8 = 1;
+
+/// Size of one entry in a delta record.
+const DELTA_ENTRY_SIZE: usize = 3;
+
+pub struct Recorder {
+    writer: BufWriter<File>,
+    start: Instant,
+    last: HashMap<u16, Frame>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+
+        Ok(Self {
+            writer,
+            start: Instant::now(),
+            last: HashMap::new(),
+        })
+    }
+
+    /// Records every universe which changed since the last call.
+    pub fn record(&mut self, frames: &[Frame]) -> io::Result<()> {
+        let timestamp = self.start.elapsed().as_millis() as u32;
+
+        for (universe, frame) in frames.iter().enumerate() {
+            let universe = universe as u16;
+            let previous = self.last.get(&universe);
+
+            if previous == Some(frame) {
+                continue;
+            }
+
+            let changed: Vec<usize> = match previous {
+                Some(previous) => (0..UNIVERSE_SIZE)
+                    .filter(|&i| previous[i] != frame[i])
+                    .collect(),
+                None => (0..UNIVERSE_SIZE).collect(),
+            };
+
+            self.writer.write_all(&timestamp.to_le_bytes())?;
+            self.writer.write_all(&universe.to_le_bytes())?;
+
+            if changed.len() * DELTA_ENTRY_SIZE < UNIVERSE_SIZE {
+                self.writer.write_all(&[RECORD_DELTA])?;
+                self.writer
+                    .write_all(&(changed.len() as u16).to_le_bytes())?;
+                for index in changed {
+                    self.writer.write_all(&(index as u16).to_le_bytes())?;
+                    self.writer.write_all(&[frame[index]])?;
+                }
+            } else {
+                self.writer.write_all(&[RECORD_FULL])?;
+                self.writer.write_all(frame)?;
+            }
+
+            self.last.insert(universe, *frame);
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+struct Record {
+    timestamp: Duration,
+    universe: u16,
+    data: RecordData,
+}
+
+enum RecordData {
+    Full(Box<Frame>),
+    Delta(Vec<(u16, u8)>),
+}
+
+impl Record {
+    fn apply(self, frames: &mut Vec<Frame>) {
+        let universe = self.universe as usize;
+        if frames.len() <= universe {
+            frames.resize(universe + 1, [0; UNIVERSE_SIZE]);
+        }
+
+        match self.data {
+            RecordData::Full(frame) => frames[universe] = *frame,
+            RecordData::Delta(changes) => {
+                for (index, value) in changes {
+                    if let Some(slot) = frames[universe].get_mut(index as usize) {
+                        *slot = value;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut buf = [0; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut buf = [0; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+/// Returns `None` at the end of the file.
+fn read_record(reader: &mut impl Read) -> io::Result<Option<Record>> {
+    let mut timestamp = [0; 4];
+    match reader.read_exact(&mut timestamp) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let timestamp = Duration::from_millis(u32::from_le_bytes(timestamp) as u64);
+
+    let universe = read_u16(reader)?;
+
+    let data = match read_u8(reader)? {
+        RECORD_FULL => {
+            let mut frame = Box::new([0; UNIVERSE_SIZE]);
+            reader.read_exact(&mut frame[..])?;
+            RecordData::Full(frame)
+        }
+        RECORD_DELTA => {
+            let count = read_u16(reader)?;
+            let changes = (0..count)
+                .map(|_| -> io::Result<(u16, u8)> { Ok((read_u16(reader)?, read_u8(reader)?)) })
+                .collect::<io::Result<_>>()?;
+            RecordData::Delta(changes)
+        }
+        kind => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown record kind {kind}"),
+            ))
+        }
+    };
+
+    Ok(Some(Record {
+        timestamp,
+        universe,
+        data,
+    }))
+}
+
+pub struct Player {
+    reader: BufReader<File>,
+    start: Instant,
+    looped: bool,
+    pending: Option<Record>,
+    frames: Vec<Frame>,
+    finished: bool,
+}
+
+impl Player {
+    pub fn open(path: &Path, looped: bool) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        let version = read_u8(&mut reader)?;
+        if &magic != MAGIC || version != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a DMX recording",
+            ));
+        }
+
+        Ok(Self {
+            reader,
+            start: Instant::now(),
+            looped,
+            pending: None,
+            frames: vec![],
+            finished: false,
+        })
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Applies all records up to the current playback position and returns the resulting frames.
+    pub fn advance(&mut self) -> io::Result<&[Frame]> {
+        let position = self.start.elapsed();
+
+        while !self.finished {
+            let record = match self.pending.take() {
+                Some(record) => record,
+                None => match read_record(&mut self.reader)? {
+                    Some(record) => record,
+                    None if self.looped => {
+                        self.reader.seek(SeekFrom::Start(HEADER_LEN))?;
+                        self.start = Instant::now();
+                        break;
+                    }
+                    None => {
+                        self.finished = true;
+                        break;
+                    }
+                },
+            };
+
+            if record.timestamp > position {
+                self.pending = Some(record);
+                break;
+            }
+
+            record.apply(&mut self.frames);
+        }
+
+        Ok(&self.frames)
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Frame, Owner, Owners, UNIVERSE_SIZE};
+
+/// Setup aid stepping a block of lit channels through a universe, to find fixtures
+/// whose start address is not what it should be.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WalkConfig {
+    pub universe: u16,
+    /// Channel (starting at 1) the walk begins at and returns to after the end of the universe.
+    pub start: u16,
+    /// Channels lit at once: 1 to walk single channels, or the footprint of a fixture.
+    pub footprint: u16,
+    pub value: u8,
+    /// Time spent on every step, or `None` to only step on next / previous.
+    pub dwell_ms: Option<u64>,
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            universe: 0,
+            start: 1,
+            footprint: 1,
+            value: 255,
+            dwell_ms: Some(1000),
+        }
+    }
+}
+
+/// Sent to the frontend whenever the walk moves on.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkState {
+    pub universe: u16,
+    /// First lit channel, starting at 1.
+    pub first: u16,
+    /// Last lit channel, inclusive.
+    pub last: u16,
+}
+
+pub struct Walk {
+    config: WalkConfig,
+    /// First lit channel, starting at 1.
+    position: u16,
+    time_of_last_step: Instant,
+}
+
+impl Walk {
+    pub fn new(mut config: WalkConfig) -> Self {
+        config.footprint = config.footprint.clamp(1, UNIVERSE_SIZE as u16);
+        config.start = config
+            .start
+            .clamp(1, UNIVERSE_SIZE as u16 - config.footprint + 1);
+
+        Self {
+            config,
+            position: config.start,
+            time_of_last_step: Instant::now(),
+        }
+    }
+
+    pub fn state(&self) -> WalkState {
+        WalkState {
+            universe: self.config.universe,
+            first: self.position,
+            last: self.position + self.config.footprint - 1,
+        }
+    }
+
+    /// Moves one footprint forwards or backwards, wrapping around at the ends of the universe.
+    pub fn step(&mut self, forward: bool) {
+        let footprint = self.config.footprint;
+        let steps = (UNIVERSE_SIZE as u16 - self.config.start + 1) / footprint;
+        let index = (self.position - self.config.start) / footprint;
+
+        let index = match forward {
+            true => (index + 1) % steps,
+            false => (index + steps - 1) % steps,
+        };
+
+        self.position = self.config.start + index * footprint;
+        self.time_of_last_step = Instant::now();
+    }
+
+    /// Steps forward once the dwell time is up. Returns whether the walk moved.
+    pub fn advance(&mut self) -> bool {
+        let Some(dwell_ms) = self.config.dwell_ms else {
+            return false;
+        };
+
+        if self.time_of_last_step.elapsed() < Duration::from_millis(dwell_ms) {
+            return false;
+        }
+
+        self.step(true);
+        true
+    }
+
+    /// Darkens the walked universe except for the current footprint.
+    pub fn apply(&self, frames: &mut Vec<Frame>, owners: &mut Vec<Owners>) {
+        let index = self.config.universe as usize;
+        if frames.len() <= index {
+            frames.resize(index + 1, [0; UNIVERSE_SIZE]);
+        }
+        if owners.len() <= index {
+            owners.resize(index + 1, [Owner::Engine; UNIVERSE_SIZE]);
+        }
+
+        let first = self.position as usize - 1;
+        let last = first + self.config.footprint as usize;
+
+        frames[index] = [0; UNIVERSE_SIZE];
+        frames[index][first..last].fill(self.config.value);
+        owners[index] = [Owner::Walk; UNIVERSE_SIZE];
+    }
+}
@@ -0,0 +1,447 @@
+pub mod analysis;
+pub mod effect;
+pub mod layer;
+pub mod modulation;
+
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{
+    audio::Signal,
+    dmx::{output::FrameBuffer, EffectOwners, Frame, UNIVERSE_SIZE},
+    fixture::{
+        color::{self, Rgb},
+        group::FixtureGroup,
+        movement,
+        patch::ResolvedFixture,
+        profile::Attribute,
+    },
+    worker::{self, Ticker},
+};
+
+use analysis::Analyzer;
+use effect::{Clock, Context, Effect, EffectConfig, Param, Values};
+use layer::Layer;
+use modulation::{Matrix, Route};
+
+/// Frames rendered per second, a little faster than the DMX output so that it never
+/// sends the same engine frame twice in a row.
+const ENGINE_RATE_HZ: f32 = 50.0;
+
+/// Attributes [`color::mix`] turns into emitter values.
+const COLOR: [Attribute; 4] = [
+    Attribute::Dimmer,
+    Attribute::Red,
+    Attribute::Green,
+    Attribute::Blue,
+];
+
+pub enum EngineCommand {
+    /// The fixtures and groups effects can target, sent whenever the patch or groups change.
+    SetRig {
+        fixtures: Vec<ResolvedFixture>,
+        groups: Vec<FixtureGroup>,
+    },
+    /// Adds a stopped effect targeting the given group. Names are unique.
+    AddEffect {
+        name: String,
+        config: EffectConfig,
+        group: String,
+        layer: String,
+        reply: Sender<Result<(), String>>,
+    },
+    RemoveEffect(String),
+    SetEffectGroup {
+        name: String,
+        group: String,
+    },
+    SetEffectLayer {
+        name: String,
+        layer: String,
+    },
+    SetEffectParam {
+        name: String,
+        param: String,
+        value: f32,
+    },
+    StartEffect(String),
+    StopEffect(String),
+    ListEffects(Sender<Vec<EffectState>>),
+    /// Replaces the layer stack, bottom layer first.
+    SetLayers(Vec<Layer>),
+    ListLayers(Sender<Vec<Layer>>),
+    /// Replaces the routes of the modulation matrix.
+    SetRoutes(Vec<Route>),
+}
+
+/// An effect as shown in the frontend.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectState {
+    pub name: String,
+    pub config: EffectConfig,
+    pub group: String,
+    pub layer: String,
+    pub running: bool,
+    pub params: Vec<Param>,
+}
+
+struct Instance {
+    name: String,
+    config: EffectConfig,
+    group: String,
+    layer: String,
+    running: bool,
+    /// Started since the last frame, [`Effect::start`] is still to be called.
+    starting: bool,
+    effect: Box<dyn Effect>,
+}
+
+impl Instance {
+    fn state(&self) -> EffectState {
+        EffectState {
+            name: self.name.clone(),
+            config: self.config.clone(),
+            group: self.group.clone(),
+            layer: self.layer.clone(),
+            running: self.running,
+            params: self.effect.params().list().to_vec(),
+        }
+    }
+}
+
+/// Runs the effects and renders their attribute values into universe frames.
+pub struct Engine {
+    fixtures: Vec<ResolvedFixture>,
+    groups: Vec<FixtureGroup>,
+    effects: Vec<Instance>,
+    layers: Vec<Layer>,
+    modulation: Matrix,
+    analyzer: Analyzer,
+    /// Values of all layers blended so far.
+    values: Values,
+    /// Values of the layer being rendered.
+    layer_values: Values,
+    /// Values of the effect being rendered.
+    effect_values: Values,
+    /// Names of the effects rendered this frame, as "effect (layer)".
+    names: Vec<String>,
+    /// The effect each value of `values` is from, as an index into `names`.
+    writers: HashMap<(u32, Attribute), u16>,
+    /// The effect each value of `layer_values` is from.
+    layer_writers: HashMap<(u32, Attribute), u16>,
+    time_of_start: Instant,
+    time_of_last_frame: Instant,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self {
+            fixtures: vec![],
+            groups: vec![],
+            effects: vec![],
+            layers: vec![Layer::default()],
+            modulation: Matrix::default(),
+            analyzer: Analyzer::default(),
+            values: Values::default(),
+            layer_values: Values::default(),
+            effect_values: Values::default(),
+            names: vec![],
+            writers: HashMap::new(),
+            layer_writers: HashMap::new(),
+            time_of_start: Instant::now(),
+            time_of_last_frame: Instant::now(),
+        }
+    }
+}
+
+impl Engine {
+    fn instance(&mut self, name: &str) -> Option<&mut Instance> {
+        let instance = self.effects.iter_mut().find(|e| e.name == name);
+        if instance.is_none() {
+            eprintln!("[engine] No effect named {name}");
+        }
+        instance
+    }
+
+    fn handle(&mut self, command: EngineCommand) {
+        match command {
+            EngineCommand::SetRig { fixtures, groups } => {
+                println!(
+                    "[engine] Rig: {} fixtures in {} groups",
+                    fixtures.len(),
+                    groups.len()
+                );
+                self.fixtures = fixtures;
+                self.groups = groups;
+            }
+            EngineCommand::AddEffect {
+                name,
+                config,
+                group,
+                layer,
+                reply,
+            } => {
+                let result = match self.effects.iter().any(|e| e.name == name) {
+                    true => Err(format!("There already is an effect named {name}")),
+                    false => {
+                        println!("[engine] Added {name}: {config:?} on {group}, layer {layer}");
+                        self.effects.push(Instance {
+                            name,
+                            effect: config.create(),
+                            config,
+                            group,
+                            layer,
+                            running: false,
+                            starting: false,
+                        });
+                        Ok(())
+                    }
+                };
+                worker::reply(reply, result);
+            }
+            EngineCommand::RemoveEffect(name) => self.effects.retain(|e| e.name != name),
+            EngineCommand::SetEffectGroup { name, group } => {
+                if let Some(instance) = self.instance(&name) {
+                    instance.group = group;
+                }
+            }
+            EngineCommand::SetEffectLayer { name, layer } => {
+                if let Some(instance) = self.instance(&name) {
+                    instance.layer = layer;
+                }
+            }
+            EngineCommand::SetEffectParam { name, param, value } => {
+                if let Some(instance) = self.instance(&name) {
+                    if let Err(err) = instance.effect.params_mut().set(&param, value) {
+                        eprintln!("[engine] {name}: {err}");
+                    }
+                }
+            }
+            EngineCommand::StartEffect(name) => {
+                if let Some(instance) = self.instance(&name) {
+                    instance.running = true;
+                    instance.starting = true;
+                }
+            }
+            EngineCommand::StopEffect(name) => {
+                if let Some(instance) = self.instance(&name) {
+                    instance.running = false;
+                }
+            }
+            EngineCommand::ListEffects(reply) => {
+                worker::reply(reply, self.effects.iter().map(Instance::state).collect());
+            }
+            EngineCommand::SetLayers(layers) => self.layers = layers,
+            EngineCommand::ListLayers(reply) => {
+                worker::reply(reply, self.layers.clone());
+            }
+            EngineCommand::SetRoutes(routes) => {
+                println!("[engine] {} modulation routes", routes.len());
+                self.modulation.set_routes(routes);
+            }
+        }
+    }
+
+    /// Applies the modulation routes, runs every started effect once, blends the layers
+    /// bottom to top and writes the result into the frames, along with the effects that
+    /// own each channel.
+    pub fn render(&mut self, frames: &mut Vec<Frame>, effects: &mut EffectOwners) {
+        let now = Instant::now();
+        let analysis = self.analyzer.frame(now);
+        let context = Context {
+            analysis: &analysis,
+            clock: Clock {
+                time: now - self.time_of_start,
+                delta: now - self.time_of_last_frame,
+            },
+        };
+        self.time_of_last_frame = now;
+
+        self.modulation.update(&analysis, context.clock.delta);
+        for (route, value) in self.modulation.values() {
+            // Routes outlive the effects they target, and may be set up before them.
+            if let Some(instance) = self.effects.iter_mut().find(|e| e.name == route.effect) {
+                let _ = instance.effect.params_mut().set(&route.param, value);
+            }
+        }
+
+        self.values.clear();
+        self.names.clear();
+        self.writers.clear();
+        for layer in &self.layers {
+            self.layer_values.clear();
+            self.layer_writers.clear();
+
+            for instance in self
+                .effects
+                .iter_mut()
+                .filter(|e| e.running && e.layer == layer.name)
+            {
+                let Some(group) = self.groups.iter().find(|g| g.name == instance.group) else {
+                    continue;
+                };
+
+                if instance.starting {
+                    instance.effect.start(&context);
+                    instance.starting = false;
+                }
+                let fixtures = group.resolve(&self.fixtures);
+                self.effect_values.clear();
+                instance
+                    .effect
+                    .render(&context, &fixtures, &mut self.effect_values);
+
+                let writer = self.names.len() as u16;
+                self.names
+                    .push(format!("{} ({})", instance.name, layer.name));
+                for (fixture, attribute, value) in self.effect_values.iter() {
+                    self.layer_values.set(fixture, attribute, value);
+                    self.layer_writers.insert((fixture, attribute), writer);
+                }
+            }
+
+            for key in layer.blend(&self.layer_values, &mut self.values) {
+                if let Some(&writer) = self.layer_writers.get(&key) {
+                    self.writers.insert(key, writer);
+                }
+            }
+        }
+        // Override layers drop the values of the attributes they leave alone.
+        self.writers
+            .retain(|&(fixture, attribute), _| self.values.get(fixture, attribute).is_some());
+
+        output(&self.fixtures, &self.values, &self.writers, frames, effects);
+        effects.names.clone_from(&self.names);
+    }
+}
+
+/// Writes the values of every fixture into the frames. Attributes no effect wrote
+/// keep the default value of their channel, unpatched channels are 0.
+///
+/// Also records which of the `writers` owns each channel: the emitters belong to the
+/// effect that wrote the dimmer or color, pan and tilt go out together.
+fn output(
+    fixtures: &[ResolvedFixture],
+    values: &Values,
+    writers: &HashMap<(u32, Attribute), u16>,
+    frames: &mut Vec<Frame>,
+    effects: &mut EffectOwners,
+) {
+    let universes = fixtures
+        .iter()
+        .map(|f| f.universe as usize + 1)
+        .max()
+        .unwrap_or(1);
+    frames.resize(universes.max(frames.len()), [0; UNIVERSE_SIZE]);
+    for frame in frames.iter_mut() {
+        frame.fill(0);
+    }
+    effects.channels.clear();
+    effects.channels.resize(frames.len(), [None; UNIVERSE_SIZE]);
+
+    let writer = |id: u32, attributes: &[Attribute]| {
+        attributes
+            .iter()
+            .find_map(|&a| writers.get(&(id, a)).copied())
+    };
+    let mut own = |fixture: &ResolvedFixture, attribute: Attribute, writer: Option<u16>| {
+        let channels = &mut effects.channels[fixture.universe as usize];
+        for index in fixture.indices_of(attribute) {
+            channels[index] = writer;
+        }
+    };
+
+    for fixture in fixtures {
+        fixture.write_defaults(frames);
+
+        let [dimmer, red, green, blue] = COLOR.map(|a| values.get(fixture.id, a));
+        if [dimmer, red, green, blue].iter().any(Option::is_some) {
+            // An intensity without a color is white light.
+            let color = match (red, green, blue) {
+                (None, None, None) => Rgb::WHITE,
+                _ => Rgb {
+                    red: red.unwrap_or_default(),
+                    green: green.unwrap_or_default(),
+                    blue: blue.unwrap_or_default(),
+                },
+            };
+            let owner = writer(fixture.id, &COLOR);
+            for (attribute, value) in color::mix(fixture, color, dimmer.unwrap_or(1.0)) {
+                fixture.write(frames, attribute, value);
+                own(fixture, attribute, owner);
+            }
+        }
+
+        let pan = values.get(fixture.id, Attribute::Pan);
+        let tilt = values.get(fixture.id, Attribute::Tilt);
+        if pan.is_some() || tilt.is_some() {
+            movement::write(
+                fixture,
+                frames,
+                pan.unwrap_or_default(),
+                tilt.unwrap_or_default(),
+            );
+            let owner = writer(fixture.id, &[Attribute::Pan, Attribute::Tilt]);
+            own(fixture, Attribute::Pan, owner);
+            own(fixture, Attribute::Tilt, owner);
+        }
+    }
+
+    // Everything else goes out as written, after the color so that effects can
+    // still set e.g. a white emitter of their own.
+    for (id, attribute, value) in values.iter() {
+        if COLOR.contains(&attribute) || matches!(attribute, Attribute::Pan | Attribute::Tilt) {
+            continue;
+        }
+        if let Some(fixture) = fixtures.iter().find(|f| f.id == id) {
+            fixture.write(frames, attribute, value);
+            own(fixture, attribute, writer(id, &[attribute]));
+        }
+    }
+}
+
+/// Spawns the thread running the effects, fed with the signals of the audio thread.
+/// The rendered frames are published to `frames` for the DMX output thread.
+pub fn spawn(
+    frames: Arc<FrameBuffer>,
+    commands: Receiver<EngineCommand>,
+    signals: Receiver<Signal>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut engine = Engine::default();
+        let period = Duration::from_secs_f32(1.0 / ENGINE_RATE_HZ);
+        let mut ticker = Ticker::default();
+
+        loop {
+            loop {
+                match commands.try_recv() {
+                    Ok(command) => engine.handle(command),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        println!("[engine] Engine thread exiting");
+                        return;
+                    }
+                }
+            }
+
+            // Signals pause while the audio thread restarts with another device.
+            while let Ok(signal) = signals.try_recv() {
+                engine.analyzer.signal(signal, Instant::now());
+            }
+
+            frames.write(|f| frames.write_effects(|e| engine.render(f, e)));
+            frames.publish();
+            ticker.wait(period);
+        }
+    })
+}
@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::audio::Signal;
+
+/// A beat level from 0.0 to 1.0 above this counts as a beat...
+const BEAT_ON: f32 = 0.6;
+/// ...once the level fell below this since the last beat.
+const BEAT_OFF: f32 = 0.3;
+
+/// Beat intervals outside of 50 to 220 BPM are taken as detection errors.
+const MIN_BEAT_INTERVAL: Duration = Duration::from_millis(272);
+const MAX_BEAT_INTERVAL: Duration = Duration::from_millis(1200);
+/// How far every new beat interval moves the tempo estimate.
+const TEMPO_SMOOTHING: f32 = 0.2;
+
+pub const BEATS_PER_BAR: u64 = 4;
+
+/// A volume below this for [`SILENCE_DURATION`] is silence.
+const SILENCE_LEVEL: f32 = 0.05;
+const SILENCE_DURATION: Duration = Duration::from_secs(1);
+/// A bass level below this for [`BREAKDOWN_DURATION`] is a breakdown...
+const BREAKDOWN_LEVEL: f32 = 0.2;
+const BREAKDOWN_DURATION: Duration = Duration::from_secs(4);
+/// ...and the bass coming back above this after one is a drop.
+const DROP_LEVEL: f32 = 0.6;
+
+/// What effects know about the music in one engine frame. Levels are from 0.0 to 1.0.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Analysis {
+    pub volume: f32,
+    /// A beat was detected since the last frame.
+    pub beat: bool,
+    /// Beats since the engine started, the bar is `beat_index / BEATS_PER_BAR`.
+    pub beat_index: u64,
+    /// Level of every frequency band, lowest first.
+    pub bands: Vec<f32>,
+    /// Beats per minute, 0.0 until two beats in a plausible interval were heard.
+    pub tempo: f32,
+    /// How far the current beat has progressed, from 0.0 to 1.0. Keeps running at
+    /// the last tempo when beats drop out.
+    pub phase: f32,
+    /// The bass came back after a breakdown, until the next breakdown.
+    pub drop: bool,
+    /// Nothing was heard for a while.
+    pub silence: bool,
+}
+
+/// Turns the signals of the audio thread into an [`Analysis`] per engine frame.
+#[derive(Default)]
+pub struct Analyzer {
+    analysis: Analysis,
+    /// The beat level fell below [`BEAT_OFF`] since the last beat.
+    armed: bool,
+    time_of_last_beat: Option<Instant>,
+    /// Smoothed interval between beats, in seconds.
+    beat_interval: Option<f32>,
+    time_of_last_sound: Option<Instant>,
+    /// Last time the bass was above [`BREAKDOWN_LEVEL`].
+    time_of_last_bass: Option<Instant>,
+}
+
+impl Analyzer {
+    pub fn signal(&mut self, signal: Signal, now: Instant) {
+        match signal {
+            Signal::Volume(volume) => {
+                self.analysis.volume = volume as f32 / 255.0;
+                if self.analysis.volume >= SILENCE_LEVEL {
+                    self.time_of_last_sound = Some(now);
+                }
+            }
+            Signal::Bass(level) => {
+                if self.analysis.bands.is_empty() {
+                    self.analysis.bands.push(0.0);
+                }
+                let level = level as f32 / 255.0;
+                self.analysis.bands[0] = level;
+
+                if level >= DROP_LEVEL && self.breakdown(now) {
+                    self.analysis.drop = true;
+                }
+                if level >= BREAKDOWN_LEVEL {
+                    self.time_of_last_bass = Some(now);
+                }
+            }
+            Signal::Beat(level) => {
+                let level = level as f32 / 255.0;
+                if self.armed && level >= BEAT_ON {
+                    self.armed = false;
+                    self.beat(now);
+                } else if level <= BEAT_OFF {
+                    self.armed = true;
+                }
+            }
+        }
+    }
+
+    fn beat(&mut self, now: Instant) {
+        if let Some(last) = self.time_of_last_beat {
+            let interval = now - last;
+            if (MIN_BEAT_INTERVAL..=MAX_BEAT_INTERVAL).contains(&interval) {
+                let interval = interval.as_secs_f32();
+                self.beat_interval = Some(match self.beat_interval {
+                    Some(smoothed) => smoothed + (interval - smoothed) * TEMPO_SMOOTHING,
+                    None => interval,
+                });
+            }
+        }
+
+        self.time_of_last_beat = Some(now);
+        self.analysis.beat = true;
+        self.analysis.beat_index += 1;
+    }
+
+    /// The bass has been quiet for [`BREAKDOWN_DURATION`]. Before the first bass the
+    /// music has not started, which is no breakdown.
+    fn breakdown(&self, now: Instant) -> bool {
+        self.time_of_last_bass
+            .is_some_and(|last| now - last >= BREAKDOWN_DURATION)
+    }
+
+    /// The analysis for the next engine frame. [`Analysis::beat`] is only set in the
+    /// first frame after a beat.
+    pub fn frame(&mut self, now: Instant) -> Analysis {
+        if let (Some(interval), Some(last)) = (self.beat_interval, self.time_of_last_beat) {
+            self.analysis.tempo = 60.0 / interval;
+            self.analysis.phase = ((now - last).as_secs_f32() / interval).fract();
+        }
+        if self.breakdown(now) {
+            self.analysis.drop = false;
+        }
+        self.analysis.silence = self
+            .time_of_last_sound
+            .is_none_or(|last| now - last >= SILENCE_DURATION);
+
+        let analysis = self.analysis.clone();
+        self.analysis.beat = false;
+        analysis
+    }
+}
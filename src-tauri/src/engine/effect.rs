@@ -0,0 +1,167 @@
+pub mod chase;
+pub mod lfo;
+pub mod pulse;
+pub mod wash;
+
+use std::{collections::HashMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixture::{patch::ResolvedFixture, profile::Attribute};
+
+use super::analysis::Analysis;
+
+/// Time as seen by effects.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Clock {
+    /// Since the engine started.
+    pub time: Duration,
+    /// Since the last frame.
+    pub delta: Duration,
+}
+
+/// Everything an effect gets to know in one engine frame.
+pub struct Context<'a> {
+    pub analysis: &'a Analysis,
+    pub clock: Clock,
+}
+
+/// Counts the beats passing from frame to frame, including fractions of a beat.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BeatCounter {
+    last_phase: f32,
+}
+
+impl BeatCounter {
+    pub fn reset(&mut self, analysis: &Analysis) {
+        self.last_phase = analysis.phase;
+    }
+
+    /// Beats that passed since the last frame. The phase keeps running at the last
+    /// tempo without beats, and when nothing set a tempo yet every beat counts fully.
+    pub fn advance(&mut self, analysis: &Analysis) -> f32 {
+        let mut beats = analysis.phase - self.last_phase;
+        if analysis.beat || beats < 0.0 {
+            beats += 1.0;
+        }
+        self.last_phase = analysis.phase;
+        beats.max(0.0)
+    }
+}
+
+/// A number an effect can be tuned with while it runs, e.g. a speed or a hue.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Param {
+    pub name: &'static str,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Params(Vec<Param>);
+
+impl Params {
+    pub fn with(mut self, name: &'static str, value: f32, min: f32, max: f32) -> Self {
+        self.0.push(Param {
+            name,
+            value,
+            min,
+            max,
+        });
+        self
+    }
+
+    pub fn list(&self) -> &[Param] {
+        &self.0
+    }
+
+    /// The value of a parameter, 0.0 for unknown ones.
+    pub fn get(&self, name: &str) -> f32 {
+        self.0
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value)
+            .unwrap_or_default()
+    }
+
+    /// Sets a parameter, clamped to its range.
+    pub fn set(&mut self, name: &str, value: f32) -> Result<(), String> {
+        let param = self
+            .0
+            .iter_mut()
+            .find(|p| p.name == name)
+            .ok_or_else(|| format!("Unknown parameter {name}"))?;
+        param.value = value.clamp(param.min, param.max);
+        Ok(())
+    }
+}
+
+/// Attribute values written by effects, per fixture id.
+///
+/// Pan and tilt are in degrees from the center, see [`crate::fixture::movement`].
+/// Everything else is from 0.0 to 1.0, with dimmer, red, green and blue being the
+/// intensity and color that [`crate::fixture::color::mix`] turns into emitter values.
+#[derive(Debug, Clone, Default)]
+pub struct Values(HashMap<(u32, Attribute), f32>);
+
+impl Values {
+    pub fn set(&mut self, fixture: u32, attribute: Attribute, value: f32) {
+        self.0.insert((fixture, attribute), value);
+    }
+
+    pub fn get(&self, fixture: u32, attribute: Attribute) -> Option<f32> {
+        self.0.get(&(fixture, attribute)).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, Attribute, f32)> + '_ {
+        self.0
+            .iter()
+            .map(|(&(fixture, attribute), &value)| (fixture, attribute, value))
+    }
+
+    /// Keeps the values for which `keep` returns true.
+    pub fn retain(&mut self, mut keep: impl FnMut(u32, Attribute) -> bool) {
+        self.0
+            .retain(|&(fixture, attribute), _| keep(fixture, attribute));
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Show logic driven by the analysis and the clock.
+pub trait Effect: Send {
+    fn params(&self) -> &Params;
+
+    fn params_mut(&mut self) -> &mut Params;
+
+    /// Called whenever the effect is (re)started, e.g. to go back to its first step.
+    fn start(&mut self, _context: &Context) {}
+
+    /// Writes the attributes of the target fixtures, given in group order.
+    fn render(&mut self, context: &Context, fixtures: &[&ResolvedFixture], values: &mut Values);
+}
+
+/// The effects there are, and how they are set up before they get parameters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum EffectConfig {
+    Pulse,
+    Wash,
+    Chase(chase::ChaseConfig),
+    Lfo(lfo::LfoConfig),
+}
+
+impl EffectConfig {
+    pub fn create(&self) -> Box<dyn Effect> {
+        match self {
+            EffectConfig::Pulse => Box::<pulse::Pulse>::default(),
+            EffectConfig::Wash => Box::<wash::Wash>::default(),
+            EffectConfig::Chase(config) => Box::new(chase::Chase::new(config.clone())),
+            EffectConfig::Lfo(config) => Box::new(lfo::Lfo::new(config.clone())),
+        }
+    }
+}
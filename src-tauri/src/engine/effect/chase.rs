@@ -0,0 +1,216 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::analysis::BEATS_PER_BAR,
+    fixture::{patch::ResolvedFixture, profile::Attribute},
+};
+
+use super::{BeatCounter, Context, Effect, Params, Values};
+
+/// When a chase moves on to its next step.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Trigger {
+    /// Every n beats.
+    Beats(u32),
+    /// Every n bars of [`BEATS_PER_BAR`] beats.
+    Bars(u32),
+    /// Every n seconds, regardless of the music.
+    Seconds(f32),
+}
+
+/// The order a chase runs through its steps in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    #[default]
+    Forward,
+    Backward,
+    /// Forward to the last step and backward to the first, without repeating either.
+    Bounce,
+    /// Any step but the current one.
+    Random,
+}
+
+/// One attribute value of a look.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LookValue {
+    pub attribute: Attribute,
+    pub value: f32,
+}
+
+/// A look on some of the fixtures of the chase.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Step {
+    /// Ids of the fixtures the look is on, every fixture of the group if empty.
+    #[serde(default)]
+    pub fixtures: Vec<u32>,
+    pub look: Vec<LookValue>,
+}
+
+impl Step {
+    fn value(&self, fixture: u32, attribute: Attribute) -> Option<f32> {
+        if !self.fixtures.is_empty() && !self.fixtures.contains(&fixture) {
+            return None;
+        }
+        self.look
+            .iter()
+            .find(|v| v.attribute == attribute)
+            .map(|v| v.value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChaseConfig {
+    pub steps: Vec<Step>,
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub direction: Direction,
+}
+
+/// Runs through a list of looks, crossfading from one to the next.
+///
+/// A fixture the next step has no dimmer value for fades out, its other attributes
+/// hold the values of the previous step.
+pub struct Chase {
+    config: ChaseConfig,
+    params: Params,
+    step: usize,
+    previous: usize,
+    /// Bounce is on its way back to the first step.
+    backward: bool,
+    /// How far the current step has progressed, from 0.0 to 1.0.
+    progress: f32,
+    beats: BeatCounter,
+}
+
+impl Chase {
+    pub fn new(config: ChaseConfig) -> Self {
+        Self {
+            config,
+            params: Params::default()
+                // Fraction of the step spent fading from the previous one.
+                .with("crossfade", 0.0, 0.0, 1.0)
+                // Multiplies the rate the steps advance at.
+                .with("speed", 1.0, 0.25, 4.0),
+            step: 0,
+            previous: 0,
+            backward: false,
+            progress: 0.0,
+            beats: BeatCounter::default(),
+        }
+    }
+
+    /// Length of a step in beats or seconds, depending on the trigger.
+    fn step_length(&self) -> f32 {
+        match self.config.trigger {
+            Trigger::Beats(beats) => beats.max(1) as f32,
+            Trigger::Bars(bars) => (bars.max(1) as u64 * BEATS_PER_BAR) as f32,
+            Trigger::Seconds(seconds) => seconds.max(0.01),
+        }
+    }
+
+    fn advance(&mut self) {
+        let count = self.config.steps.len();
+        self.previous = self.step;
+        self.step = match self.config.direction {
+            Direction::Forward => (self.step + 1) % count,
+            Direction::Backward => (self.step + count - 1) % count,
+            Direction::Bounce if count < 2 => 0,
+            Direction::Bounce => {
+                if self.step == 0 {
+                    self.backward = false;
+                } else if self.step == count - 1 {
+                    self.backward = true;
+                }
+                match self.backward {
+                    true => self.step - 1,
+                    false => self.step + 1,
+                }
+            }
+            Direction::Random if count < 2 => 0,
+            Direction::Random => (self.step + fastrand::usize(1..count)) % count,
+        };
+    }
+}
+
+impl Effect for Chase {
+    fn params(&self) -> &Params {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut Params {
+        &mut self.params
+    }
+
+    fn start(&mut self, context: &Context) {
+        self.step = match self.config.direction {
+            Direction::Backward => self.config.steps.len().saturating_sub(1),
+            _ => 0,
+        };
+        self.previous = self.step;
+        self.backward = false;
+        self.beats.reset(context.analysis);
+
+        // Beat chases start on the beat grid, like the bars of the analysis.
+        let length = self.step_length();
+        self.progress = match self.config.trigger {
+            Trigger::Seconds(_) => 0.0,
+            _ => {
+                let beats = context.analysis.beat_index as f32 + context.analysis.phase;
+                (beats % length) / length
+            }
+        };
+    }
+
+    fn render(&mut self, context: &Context, fixtures: &[&ResolvedFixture], values: &mut Values) {
+        if self.config.steps.is_empty() {
+            return;
+        }
+
+        let elapsed = match self.config.trigger {
+            Trigger::Seconds(_) => context.clock.delta.as_secs_f32(),
+            _ => self.beats.advance(context.analysis),
+        };
+        self.progress += elapsed * self.params.get("speed") / self.step_length();
+        while self.progress >= 1.0 {
+            self.progress -= 1.0;
+            self.advance();
+        }
+
+        let crossfade = self.params.get("crossfade");
+        let fade = match crossfade > 0.0 {
+            true => (self.progress / crossfade).min(1.0),
+            false => 1.0,
+        };
+
+        let from = &self.config.steps[self.previous];
+        let to = &self.config.steps[self.step];
+        let attributes: HashSet<Attribute> = from
+            .look
+            .iter()
+            .chain(&to.look)
+            .map(|v| v.attribute)
+            .collect();
+
+        for fixture in fixtures {
+            for &attribute in &attributes {
+                let from = from.value(fixture.id, attribute);
+                let to = to.value(fixture.id, attribute);
+                let (from, to) = match (from, to, attribute) {
+                    (None, None, _) => continue,
+                    (from, to, Attribute::Dimmer) => (from.unwrap_or(0.0), to.unwrap_or(0.0)),
+                    (Some(from), None, _) => (from, from),
+                    (None, Some(to), _) => (to, to),
+                    (Some(from), Some(to), _) => (from, to),
+                };
+                values.set(fixture.id, attribute, from + (to - from) * fade);
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::{collections::HashMap, f32::consts::TAU};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixture::{group, patch::ResolvedFixture, profile::Attribute};
+
+use super::{BeatCounter, Context, Effect, Params, Values};
+
+/// The waveform of an [`Lfo`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Shape {
+    #[default]
+    Sine,
+    Triangle,
+    /// Rises over the cycle and drops back at its end.
+    Saw,
+    Square,
+    /// A random value, held for one cycle.
+    SampleAndHold,
+}
+
+impl Shape {
+    /// The wave at `position` cycles, from -1.0 to 1.0. Sample and hold is handled
+    /// by the [`Lfo`], it needs a value per fixture and cycle.
+    fn wave(self, position: f32) -> f32 {
+        let cycle = position.rem_euclid(1.0);
+        match self {
+            Shape::Sine => (cycle * TAU).sin(),
+            Shape::Triangle => 1.0 - 4.0 * (cycle - 0.5).abs(),
+            Shape::Saw => 2.0 * cycle - 1.0,
+            Shape::Square if cycle < 0.5 => 1.0,
+            Shape::Square => -1.0,
+            Shape::SampleAndHold => 0.0,
+        }
+    }
+}
+
+/// How fast an [`Lfo`] runs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Rate {
+    /// Cycles per second.
+    Hz(f32),
+    /// Beats per cycle, e.g. 0.5 for two cycles per beat or 4.0 for one per bar.
+    Beats(f32),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LfoConfig {
+    pub shape: Shape,
+    pub rate: Rate,
+    pub attribute: Attribute,
+}
+
+/// Moves one attribute of every fixture along a waveform, e.g. a dimmer wave running
+/// across a truss.
+///
+/// The value is `offset + depth * wave`, in the unit of the attribute: degrees for
+/// pan and tilt, 0.0 to 1.0 otherwise.
+pub struct Lfo {
+    config: LfoConfig,
+    params: Params,
+    /// Cycles since the start.
+    position: f32,
+    beats: BeatCounter,
+    /// Cycle and value of the last sample per fixture, for sample and hold.
+    samples: HashMap<u32, (i64, f32)>,
+}
+
+impl Lfo {
+    pub fn new(config: LfoConfig) -> Self {
+        let params = match config.attribute {
+            Attribute::Pan | Attribute::Tilt => Params::default()
+                .with("depth", 45.0, 0.0, 270.0)
+                .with("offset", 0.0, -270.0, 270.0),
+            _ => Params::default()
+                .with("depth", 0.5, 0.0, 1.0)
+                .with("offset", 0.5, 0.0, 1.0),
+        };
+
+        Self {
+            config,
+            params: params
+                // Cycles fanned out over the group, 1.0 puts one full wave across it.
+                .with("spread", 0.0, 0.0, 4.0)
+                // Multiplies the rate.
+                .with("speed", 1.0, 0.25, 4.0),
+            position: 0.0,
+            beats: BeatCounter::default(),
+            samples: HashMap::new(),
+        }
+    }
+
+    fn sample(&mut self, fixture: u32, position: f32) -> f32 {
+        let cycle = position.floor() as i64;
+        match self.samples.get(&fixture) {
+            Some(&(sampled, value)) if sampled == cycle => value,
+            _ => {
+                let value = fastrand::f32() * 2.0 - 1.0;
+                self.samples.insert(fixture, (cycle, value));
+                value
+            }
+        }
+    }
+}
+
+impl Effect for Lfo {
+    fn params(&self) -> &Params {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut Params {
+        &mut self.params
+    }
+
+    fn start(&mut self, context: &Context) {
+        self.beats.reset(context.analysis);
+        self.samples.clear();
+
+        // Beat locked waves start on the beat grid, so that e.g. a wave of one bar
+        // peaks on the same beat of every bar.
+        self.position = match self.config.rate {
+            Rate::Hz(_) => 0.0,
+            Rate::Beats(beats) => {
+                let analysis = context.analysis;
+                (analysis.beat_index as f32 + analysis.phase) / beats.max(0.01)
+            }
+        };
+    }
+
+    fn render(&mut self, context: &Context, fixtures: &[&ResolvedFixture], values: &mut Values) {
+        let speed = self.params.get("speed");
+        self.position += match self.config.rate {
+            Rate::Hz(hz) => context.clock.delta.as_secs_f32() * hz * speed,
+            Rate::Beats(beats) => self.beats.advance(context.analysis) * speed / beats.max(0.01),
+        };
+
+        let depth = self.params.get("depth");
+        let offset = self.params.get("offset");
+        let spread = self.params.get("spread");
+        for (index, fixture) in fixtures.iter().enumerate() {
+            let position = self.position - spread * group::spread(index, fixtures.len());
+            let wave = match self.config.shape {
+                Shape::SampleAndHold => self.sample(fixture.id, position),
+                shape => shape.wave(position),
+            };
+
+            let value = offset + depth * wave;
+            let value = match self.config.attribute {
+                Attribute::Pan | Attribute::Tilt => value,
+                _ => value.clamp(0.0, 1.0),
+            };
+            values.set(fixture.id, self.config.attribute, value);
+        }
+    }
+}
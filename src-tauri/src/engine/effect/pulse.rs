@@ -0,0 +1,50 @@
+use crate::fixture::{patch::ResolvedFixture, profile::Attribute};
+
+use super::{Context, Effect, Params, Values};
+
+/// Flashes the dimmers on every beat and lets them fade out.
+pub struct Pulse {
+    params: Params,
+    /// Level of the last flash, fading towards 0.0.
+    level: f32,
+}
+
+impl Default for Pulse {
+    fn default() -> Self {
+        Self {
+            params: Params::default()
+                .with("level", 1.0, 0.0, 1.0)
+                // Seconds from the flash to dark.
+                .with("decay", 0.3, 0.05, 2.0),
+            level: 0.0,
+        }
+    }
+}
+
+impl Effect for Pulse {
+    fn params(&self) -> &Params {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut Params {
+        &mut self.params
+    }
+
+    fn start(&mut self, _context: &Context) {
+        self.level = 0.0;
+    }
+
+    fn render(&mut self, context: &Context, fixtures: &[&ResolvedFixture], values: &mut Values) {
+        if context.analysis.beat {
+            self.level = self.params.get("level");
+        } else {
+            let decay = self.params.get("decay");
+            self.level -= context.clock.delta.as_secs_f32() / decay;
+            self.level = self.level.max(0.0);
+        }
+
+        for fixture in fixtures {
+            values.set(fixture.id, Attribute::Dimmer, self.level);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::fixture::{
+    color::{Hsv, Rgb},
+    group,
+    patch::ResolvedFixture,
+    profile::Attribute,
+};
+
+use super::{Context, Effect, Params, Values};
+
+/// One color on every fixture, optionally fanned out over the group and rotating
+/// through the hues.
+pub struct Wash {
+    params: Params,
+}
+
+impl Default for Wash {
+    fn default() -> Self {
+        Self {
+            params: Params::default()
+                .with("hue", 0.0, 0.0, 360.0)
+                .with("saturation", 1.0, 0.0, 1.0)
+                .with("intensity", 1.0, 0.0, 1.0)
+                // Hue difference between the first and the last fixture of the group.
+                .with("spread", 0.0, 0.0, 360.0)
+                // Degrees per beat, hues stand still without beats.
+                .with("rotation", 0.0, -180.0, 180.0),
+        }
+    }
+}
+
+impl Effect for Wash {
+    fn params(&self) -> &Params {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut Params {
+        &mut self.params
+    }
+
+    fn render(&mut self, context: &Context, fixtures: &[&ResolvedFixture], values: &mut Values) {
+        let beats = context.analysis.beat_index as f32 + context.analysis.phase;
+        let hue = self.params.get("hue") + self.params.get("rotation") * beats;
+        let intensity = self.params.get("intensity");
+
+        for (index, fixture) in fixtures.iter().enumerate() {
+            let color: Rgb = Hsv {
+                hue: hue + self.params.get("spread") * group::spread(index, fixtures.len()),
+                saturation: self.params.get("saturation"),
+                value: 1.0,
+            }
+            .into();
+
+            values.set(fixture.id, Attribute::Dimmer, intensity);
+            values.set(fixture.id, Attribute::Red, color.red);
+            values.set(fixture.id, Attribute::Green, color.green);
+            values.set(fixture.id, Attribute::Blue, color.blue);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixture::profile::Attribute;
+
+use super::effect::Values;
+
+/// How the values of a layer combine with those of the layers below it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum BlendMode {
+    /// Highest takes precedence.
+    Htp,
+    /// Latest takes precedence: the layer replaces what it writes.
+    #[default]
+    Ltp,
+    Add,
+    /// Scales the values below, e.g. to fade a chase in and out. Attributes nothing
+    /// below wrote stay unwritten.
+    Multiply,
+    /// Like [`BlendMode::Ltp`], but fixtures the layer writes also lose the values of
+    /// every attribute the layer leaves alone.
+    Override,
+}
+
+/// One level of the stack effects are rendered in, the first layer being the bottom.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Layer {
+    /// Unique within the stack, effects refer to layers by name.
+    pub name: String,
+    /// 0.0 leaves the values below untouched, 1.0 applies the layer fully.
+    pub opacity: f32,
+    pub blend: BlendMode,
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Self {
+            name: "Base".to_string(),
+            opacity: 1.0,
+            blend: BlendMode::Ltp,
+        }
+    }
+}
+
+impl Layer {
+    /// Blends the values rendered on this layer into those of the layers below.
+    ///
+    /// Returns the attributes the layer now owns: those a HTP layer won and every one
+    /// written by the other modes, except multiply, which only scales what is below.
+    pub fn blend(&self, layer: &Values, below: &mut Values) -> Vec<(u32, Attribute)> {
+        let mut owned = vec![];
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if opacity == 0.0 {
+            return owned;
+        }
+
+        if self.blend == BlendMode::Override {
+            let fixtures: HashSet<u32> = layer.iter().map(|(fixture, ..)| fixture).collect();
+            below.retain(|fixture, _| !fixtures.contains(&fixture));
+        }
+
+        for (fixture, attribute, value) in layer.iter() {
+            let current = below.get(fixture, attribute);
+            let blended = match self.blend {
+                BlendMode::Htp => current.unwrap_or_default().max(value),
+                BlendMode::Ltp | BlendMode::Override => value,
+                // Pan and tilt are in degrees, there is no full level to stop at.
+                BlendMode::Add if matches!(attribute, Attribute::Pan | Attribute::Tilt) => {
+                    current.unwrap_or_default() + value
+                }
+                BlendMode::Add => (current.unwrap_or_default() + value).min(1.0),
+                BlendMode::Multiply => match current {
+                    Some(current) => current * value,
+                    None => continue,
+                },
+            };
+
+            let current = current.unwrap_or_default();
+            below.set(fixture, attribute, current + (blended - current) * opacity);
+
+            let wins = match self.blend {
+                BlendMode::Htp => value >= current,
+                BlendMode::Multiply => false,
+                _ => true,
+            };
+            if wins {
+                owned.push((fixture, attribute));
+            }
+        }
+        owned
+    }
+}
+
+/// Checks that every layer has a name of its own.
+pub fn validate(layers: &[Layer]) -> Result<(), String> {
+    for (i, layer) in layers.iter().enumerate() {
+        if layer.name.trim().is_empty() {
+            return Err("Layers need a name".to_string());
+        }
+        if layers[..i].iter().any(|l| l.name == layer.name) {
+            return Err(format!("There is more than one layer named {}", layer.name));
+        }
+    }
+    Ok(())
+}
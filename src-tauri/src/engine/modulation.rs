@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fixture::curve::Curve;
+
+use super::analysis::Analysis;
+
+/// What a [`Route`] reads from the analysis, as a level from 0.0 to 1.0.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Source {
+    Volume,
+    /// 1.0 in the frame of a beat, 0.0 otherwise. Smoothing turns it into a decay.
+    Beat,
+    /// Counts up from 0.0 on the first to 1.0 on the nth beat, then starts over.
+    BeatIndex(u32),
+    /// Level of a frequency band, 0 being the lowest.
+    Band(usize),
+    Drop,
+    Silence,
+}
+
+impl Source {
+    fn level(self, analysis: &Analysis) -> f32 {
+        let level = match self {
+            Source::Volume => analysis.volume,
+            Source::Beat => analysis.beat as u8 as f32,
+            Source::BeatIndex(count) => {
+                let count = count.max(1) as u64;
+                (analysis.beat_index % count) as f32 / (count - 1).max(1) as f32
+            }
+            Source::Band(band) => analysis.bands.get(band).copied().unwrap_or_default(),
+            Source::Drop => analysis.drop as u8 as f32,
+            Source::Silence => analysis.silence as u8 as f32,
+        };
+        level.clamp(0.0, 1.0)
+    }
+}
+
+/// Feeds a source into a parameter of an effect, e.g. the bass into the intensity of
+/// a wash or the volume into the speed of a chase.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    pub source: Source,
+    /// Name of the effect, routes to effects that do not exist do nothing.
+    pub effect: String,
+    pub param: String,
+    /// Parameter value at a source level of 0.0...
+    pub min: f32,
+    /// ...and at 1.0. May be below `min` to turn the range around.
+    pub max: f32,
+    #[serde(default)]
+    pub curve: Curve,
+    /// Seconds the level takes to follow about two thirds of a change, 0.0 to follow
+    /// instantly.
+    #[serde(default)]
+    pub smoothing: f32,
+    #[serde(default)]
+    pub invert: bool,
+}
+
+/// Checks that every route has a target and sensible numbers.
+pub fn validate(routes: &[Route]) -> Result<(), String> {
+    for route in routes {
+        if route.effect.is_empty() || route.param.is_empty() {
+            return Err("Routes need an effect and a parameter".to_string());
+        }
+        if !route.min.is_finite() || !route.max.is_finite() {
+            return Err(format!(
+                "Invalid range for {}: {}",
+                route.effect, route.param
+            ));
+        }
+        if !route.smoothing.is_finite() || route.smoothing < 0.0 {
+            return Err(format!(
+                "Invalid smoothing for {}: {}",
+                route.effect, route.param
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The routes and their smoothed levels.
+#[derive(Debug, Clone, Default)]
+pub struct Matrix {
+    routes: Vec<Route>,
+    levels: Vec<f32>,
+}
+
+impl Matrix {
+    pub fn set_routes(&mut self, routes: Vec<Route>) {
+        self.levels = vec![0.0; routes.len()];
+        self.routes = routes;
+    }
+
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Moves the level of every route towards its source, `delta` after the last update.
+    pub fn update(&mut self, analysis: &Analysis, delta: Duration) {
+        for (route, level) in self.routes.iter().zip(&mut self.levels) {
+            let mut target = route.source.level(analysis);
+            if route.invert {
+                target = 1.0 - target;
+            }
+            let target = route.curve.apply(target);
+
+            *level = match route.smoothing > 0.0 {
+                true => {
+                    let follow = 1.0 - (-delta.as_secs_f32() / route.smoothing).exp();
+                    *level + (target - *level) * follow
+                }
+                false => target,
+            };
+        }
+    }
+
+    /// Every route with the parameter value it currently asks for.
+    pub fn values(&self) -> impl Iterator<Item = (&Route, f32)> {
+        self.routes
+            .iter()
+            .zip(&self.levels)
+            .map(|(route, level)| (route, route.min + (route.max - route.min) * level))
+    }
+}
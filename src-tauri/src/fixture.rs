@@ -0,0 +1,112 @@
+pub mod color;
+pub mod curve;
+pub mod group;
+pub mod import;
+pub mod movement;
+pub mod mvr;
+pub mod patch;
+pub mod profile;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use profile::FixtureProfile;
+
+/// Name of the directory (inside the app config directory) holding user fixture profiles.
+pub const LIBRARY_DIR: &str = "fixtures";
+
+/// Profiles shipped with Blaulicht, so that simple rigs work without writing any.
+const BUILTIN_PROFILES: &[&str] = &[
+    include_str!("../fixtures/generic-dimmer.json"),
+    include_str!("../fixtures/generic-rgb-par.json"),
+];
+
+pub fn builtin() -> Vec<FixtureProfile> {
+    BUILTIN_PROFILES
+        .iter()
+        .map(|raw| serde_json::from_str(raw).expect("builtin fixture profiles are valid"))
+        .collect()
+}
+
+/// Parses a profile from a `.json` or `.toml` file.
+pub fn load_profile(path: &Path) -> Result<FixtureProfile, String> {
+    let raw = fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    let profile: FixtureProfile = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&raw).map_err(|err| err.to_string())?,
+        Some("toml") => toml::from_str(&raw).map_err(|err| err.to_string())?,
+        _ => return Err("Fixture profiles must be .json or .toml files".to_string()),
+    };
+
+    profile.validate()?;
+    Ok(profile)
+}
+
+/// Returns the builtin profiles and every valid profile in `dir`.
+/// Profiles in `dir` replace builtin ones with the same id.
+pub fn load_library(dir: &Path) -> Vec<FixtureProfile> {
+    let mut profiles = builtin();
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return profiles,
+        Err(err) => {
+            eprintln!("[fixture] Failed to read {}: {err}", dir.display());
+            return profiles;
+        }
+    };
+
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| Some(e.ok()?.path())).collect();
+    paths.sort();
+
+    for path in paths {
+        // Original GDTF files are kept next to the profiles imported from them.
+        if path.extension().is_some_and(|e| e == "gdtf") {
+            continue;
+        }
+
+        match load_profile(&path) {
+            Ok(profile) => {
+                profiles.retain(|p| p.id() != profile.id());
+                profiles.push(profile);
+            }
+            Err(err) => eprintln!("[fixture] Skipping {}: {err}", path.display()),
+        }
+    }
+
+    profiles
+}
+
+/// Writes the profile into `dir` as JSON, replacing an earlier version of it.
+pub fn save_profile(dir: &Path, profile: &FixtureProfile) -> Result<PathBuf, String> {
+    profile.validate()?;
+    fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+
+    let path = dir.join(format!("{}.json", file_stem(profile)));
+    let raw = serde_json::to_string_pretty(profile).map_err(|err| err.to_string())?;
+    fs::write(&path, raw).map_err(|err| err.to_string())?;
+    Ok(path)
+}
+
+/// Where the GDTF file a profile was imported from is kept, so that it can be
+/// passed on unchanged, e.g. in an MVR export.
+pub fn gdtf_path(dir: &Path, profile: &FixtureProfile) -> PathBuf {
+    dir.join(format!("{}.gdtf", file_stem(profile)))
+}
+
+/// Derives a file name like `eurolite-led-par-56` from the profile id.
+fn file_stem(profile: &FixtureProfile) -> String {
+    let slug: String = profile
+        .id()
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '-',
+        })
+        .collect();
+
+    let slug: Vec<&str> = slug.split('-').filter(|s| !s.is_empty()).collect();
+    slug.join("-")
+}
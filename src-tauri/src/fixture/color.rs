@@ -0,0 +1,211 @@
+use std::ops::Sub;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::Frame;
+
+use super::{patch::ResolvedFixture, profile::Attribute};
+
+/// An additive color, every component from 0.0 to 1.0.
+///
+/// Effects pick colors in RGB or [`Hsv`] plus an intensity, and [`mix`] turns them into
+/// whatever emitters a fixture has, so that one effect looks alike on mixed fixtures.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Rgb {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+}
+
+/// Hue in degrees, saturation and value from 0.0 to 1.0.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Hsv {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+/// Amber emitters look roughly like this mix of red and green.
+const AMBER: Rgb = Rgb {
+    red: 1.0,
+    green: 0.5,
+    blue: 0.0,
+};
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb {
+        red: 1.0,
+        green: 1.0,
+        blue: 1.0,
+    };
+
+    pub fn clamped(self) -> Rgb {
+        Rgb {
+            red: self.red.clamp(0.0, 1.0),
+            green: self.green.clamp(0.0, 1.0),
+            blue: self.blue.clamp(0.0, 1.0),
+        }
+    }
+
+    fn max(self) -> f32 {
+        self.red.max(self.green).max(self.blue)
+    }
+
+    fn min(self) -> f32 {
+        self.red.min(self.green).min(self.blue)
+    }
+
+    /// The same color at full brightness, e.g. dark red becomes red.
+    fn normalized(self) -> Rgb {
+        match self.max() {
+            max if max > 0.0 => Rgb {
+                red: self.red / max,
+                green: self.green / max,
+                blue: self.blue / max,
+            },
+            _ => Rgb::WHITE,
+        }
+    }
+
+    fn scaled(self, level: f32) -> Rgb {
+        Rgb {
+            red: self.red * level,
+            green: self.green * level,
+            blue: self.blue * level,
+        }
+    }
+}
+
+impl Sub<f32> for Rgb {
+    type Output = Rgb;
+
+    fn sub(self, amount: f32) -> Rgb {
+        Rgb {
+            red: self.red - amount,
+            green: self.green - amount,
+            blue: self.blue - amount,
+        }
+    }
+}
+
+impl From<Hsv> for Rgb {
+    fn from(hsv: Hsv) -> Rgb {
+        let hue = hsv.hue.rem_euclid(360.0) / 60.0;
+        let chroma = hsv.value * hsv.saturation.clamp(0.0, 1.0);
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+        let (red, green, blue) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let m = hsv.value - chroma;
+        Rgb {
+            red: red + m,
+            green: green + m,
+            blue: blue + m,
+        }
+        .clamped()
+    }
+}
+
+impl From<Rgb> for Hsv {
+    fn from(rgb: Rgb) -> Hsv {
+        let (max, min) = (rgb.max(), rgb.min());
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == rgb.red {
+            60.0 * ((rgb.green - rgb.blue) / delta).rem_euclid(6.0)
+        } else if max == rgb.green {
+            60.0 * ((rgb.blue - rgb.red) / delta + 2.0)
+        } else {
+            60.0 * ((rgb.red - rgb.green) / delta + 4.0)
+        };
+
+        Hsv {
+            hue,
+            saturation: if max > 0.0 { delta / max } else { 0.0 },
+            value: max,
+        }
+    }
+}
+
+/// Converts a color and intensity into values for the emitters of a fixture:
+///
+/// - A dimmer takes the intensity, the color is then mixed at full level.
+/// - White and amber take their share out of RGB.
+/// - CMY filters subtract the complementary color from a white beam.
+/// - Fixtures with white light only get CTO or CTB from how warm the color is.
+///
+/// UV is not part of the visible color, effects write it directly.
+pub fn mix(fixture: &ResolvedFixture, color: Rgb, intensity: f32) -> Vec<(Attribute, f32)> {
+    let color = color.clamped();
+    let intensity = intensity.clamp(0.0, 1.0);
+    let mut values = vec![];
+
+    // Mixing at full level with a dimmer keeps the color from shifting at low intensity.
+    // Without one, the curve shapes the intensity once, so that the emitters keep the
+    // ratios of the color.
+    let (color, level) = match fixture.has(Attribute::Dimmer) {
+        true => {
+            values.push((Attribute::Dimmer, intensity * color.max()));
+            (color.normalized(), 1.0)
+        }
+        false => (color, fixture.intensity_curve.apply(intensity)),
+    };
+
+    let additive = [Attribute::Red, Attribute::Green, Attribute::Blue];
+    let subtractive = [Attribute::Cyan, Attribute::Magenta, Attribute::Yellow];
+
+    if additive.iter().any(|a| fixture.has(*a)) {
+        let mut rest = color;
+
+        if fixture.has(Attribute::White) {
+            let white = rest.min();
+            values.push((Attribute::White, white * level));
+            rest = rest - white;
+        }
+
+        if fixture.has(Attribute::Amber) {
+            let amber = rest.red.min(rest.green / AMBER.green).clamp(0.0, 1.0);
+            values.push((Attribute::Amber, amber * level));
+            rest.red -= amber * AMBER.red;
+            rest.green -= amber * AMBER.green;
+        }
+
+        let rest = rest.clamped().scaled(level);
+        values.push((Attribute::Red, rest.red));
+        values.push((Attribute::Green, rest.green));
+        values.push((Attribute::Blue, rest.blue));
+    } else if subtractive.iter().any(|a| fixture.has(*a)) {
+        let color = color.scaled(level);
+        values.push((Attribute::Cyan, 1.0 - color.red));
+        values.push((Attribute::Magenta, 1.0 - color.green));
+        values.push((Attribute::Yellow, 1.0 - color.blue));
+    } else {
+        if fixture.has(Attribute::White) {
+            values.push((Attribute::White, color.max() * level));
+        }
+
+        let warmth = color.red - color.blue;
+        values.push((Attribute::Cto, warmth.max(0.0)));
+        values.push((Attribute::Ctb, (-warmth).max(0.0)));
+    }
+
+    values
+}
+
+/// Writes a color and intensity to the fixture, see [`mix`].
+pub fn write(fixture: &ResolvedFixture, frames: &mut [Frame], color: Rgb, intensity: f32) {
+    for (attribute, value) in mix(fixture, color, intensity) {
+        fixture.write(frames, attribute, value);
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// How an intensity from 0.0 to 1.0 maps to the output of a fixture.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Curve {
+    #[default]
+    Linear,
+    /// Slow at the low end, where LEDs are the least smooth.
+    Square,
+    /// The inverse of [`Curve::Square`], fast at the low end.
+    InverseSquare,
+    /// Slow at both ends.
+    SCurve,
+    /// Outputs for evenly spaced inputs from 0.0 to 1.0, interpolated in between.
+    Custom(Vec<f32>),
+}
+
+impl Curve {
+    /// Maps a value from 0.0 to 1.0 onto the curve.
+    pub fn apply(&self, value: f32) -> f32 {
+        match self {
+            Curve::Linear => value,
+            Curve::Square => value * value,
+            Curve::InverseSquare => value.sqrt(),
+            Curve::SCurve => value * value * (3.0 - 2.0 * value),
+            Curve::Custom(table) => match table.len() {
+                0 => value,
+                1 => table[0],
+                len => {
+                    let position = value * (len - 1) as f32;
+                    let index = (position as usize).min(len - 2);
+                    let fraction = position - index as f32;
+                    table[index] + (table[index + 1] - table[index]) * fraction
+                }
+            },
+        }
+    }
+}
+
+/// Intensity curve and output limits of one fixture, applied to its dimmer or, for
+/// fixtures without one, to the intensity before it is mixed into the emitters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IntensityCurve {
+    pub curve: Curve,
+    /// Output at the lowest intensity above zero, e.g. where an LED stops flickering.
+    /// Zero stays zero, so that the fixture can still be switched off.
+    pub min: f32,
+    /// Output at full intensity.
+    pub max: f32,
+}
+
+impl Default for IntensityCurve {
+    fn default() -> Self {
+        Self {
+            curve: Curve::Linear,
+            min: 0.0,
+            max: 1.0,
+        }
+    }
+}
+
+impl IntensityCurve {
+    pub fn apply(&self, value: f32) -> f32 {
+        if value <= 0.0 {
+            return 0.0;
+        }
+
+        let shaped = self.curve.apply(value.min(1.0)).clamp(0.0, 1.0);
+        (self.min + shaped * (self.max - self.min)).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use super::patch::ResolvedFixture;
+
+/// A named, ordered set of fixtures, e.g. "PARs truss 1" or "Left moving heads".
+///
+/// Effects target groups rather than channels and spread their parameters along the
+/// order of the group, see [`spread`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureGroup {
+    /// Unique within the show, effects refer to groups by name.
+    pub name: String,
+    /// Fixture ids in effect order.
+    pub fixtures: Vec<u32>,
+}
+
+impl FixtureGroup {
+    /// The patched fixtures of the group in group order. Fixtures that are no longer
+    /// patched or cannot be output are left out.
+    pub fn resolve<'a>(&self, fixtures: &'a [ResolvedFixture]) -> Vec<&'a ResolvedFixture> {
+        self.fixtures
+            .iter()
+            .filter_map(|id| fixtures.iter().find(|f| f.id == *id))
+            .collect()
+    }
+}
+
+/// Derives a new fixture order from an existing one, e.g. to build a group from another.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Selection {
+    /// The 1st, 3rd, 5th... fixture.
+    Odd,
+    /// The 2nd, 4th, 6th... fixture.
+    Even,
+    Reverse,
+    Shuffle,
+    First(usize),
+    Last(usize),
+}
+
+impl Selection {
+    pub fn apply(self, fixtures: &[u32]) -> Vec<u32> {
+        match self {
+            Selection::Odd => fixtures.iter().step_by(2).copied().collect(),
+            Selection::Even => fixtures.iter().skip(1).step_by(2).copied().collect(),
+            Selection::Reverse => fixtures.iter().rev().copied().collect(),
+            Selection::Shuffle => {
+                let mut fixtures = fixtures.to_vec();
+                fastrand::shuffle(&mut fixtures);
+                fixtures
+            }
+            Selection::First(count) => fixtures.iter().take(count).copied().collect(),
+            Selection::Last(count) => fixtures[fixtures.len().saturating_sub(count)..].to_vec(),
+        }
+    }
+}
+
+/// Where the fixture at `index` sits in a group of `count` fixtures, from 0.0 for the
+/// first to just below 1.0 for the last, so that phases spread over a full cycle wrap
+/// around without the first and last fixture doubling up.
+pub fn spread(index: usize, count: usize) -> f32 {
+    match count {
+        0 => 0.0,
+        count => index as f32 / count as f32,
+    }
+}
+
+/// Checks that every group has a name of its own.
+pub fn validate(groups: &[FixtureGroup]) -> Result<(), String> {
+    for (i, group) in groups.iter().enumerate() {
+        if group.name.trim().is_empty() {
+            return Err("Fixture groups need a name".to_string());
+        }
+        if groups[..i].iter().any(|g| g.name == group.name) {
+            return Err(format!("There is more than one group named {}", group.name));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+pub mod gdtf;
+pub mod ofl;
+pub mod qxf;
+
+use std::{fs, path::Path};
+
+use serde::Serialize;
+
+use super::profile::{ChannelRange, FixtureProfile};
+
+/// A profile converted from another tool's format.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub profile: FixtureProfile,
+    /// Everything that had no equivalent in Blaulicht, e.g. "Color Wheel: WheelSlot".
+    /// Affected channels are imported as generic channels.
+    pub unmapped: Vec<String>,
+}
+
+/// Imports a fixture definition, picking the format from the file extension. JSON files
+/// are either OFL fixtures or Blaulicht profiles, e.g. from another machine.
+pub fn import(path: &Path) -> Result<ImportReport, String> {
+    let report = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            let raw = fs::read_to_string(path).map_err(|err| err.to_string())?;
+            match ofl::is_ofl(&raw) {
+                true => ofl::import(path)?,
+                false => ImportReport {
+                    profile: super::load_profile(path)?,
+                    unmapped: vec![],
+                },
+            }
+        }
+        Some("qxf") => qxf::import(path)?,
+        Some("gdtf") => gdtf::import(path)?,
+        _ => return Err(format!("Unsupported fixture format: {}", path.display())),
+    };
+
+    report.profile.validate()?;
+    Ok(report)
+}
+
+/// The ranges of an imported channel, if there is more than one. A single range
+/// spanning the whole channel carries no information.
+fn informative_ranges(ranges: Vec<ChannelRange>) -> Vec<ChannelRange> {
+    match ranges.len() {
+        0 | 1 => vec![],
+        _ => ranges,
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek},
+    path::Path,
+};
+
+use roxmltree::{Document, Node};
+use zip::ZipArchive;
+
+use crate::fixture::profile::{Attribute, ChannelDefinition, ChannelRange, FixtureProfile, Mode};
+
+use super::{informative_ranges, ImportReport};
+
+/// Name of the fixture description inside a GDTF archive.
+const DESCRIPTION_FILE: &str = "description.xml";
+
+/// Imports a GDTF file.
+pub fn import(path: &Path) -> Result<ImportReport, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    read(file)
+}
+
+/// Imports a GDTF archive from any reader, e.g. one embedded in an MVR file.
+pub fn read<R: Read + Seek>(reader: R) -> Result<ImportReport, String> {
+    let mut archive = ZipArchive::new(reader).map_err(|err| err.to_string())?;
+    let mut description = archive
+        .by_name(DESCRIPTION_FILE)
+        .map_err(|err| format!("{DESCRIPTION_FILE}: {err}"))?;
+
+    let mut raw = String::new();
+    description
+        .read_to_string(&mut raw)
+        .map_err(|err| err.to_string())?;
+    parse(&raw)
+}
+
+/// A DMX value as written by GDTF, e.g. `32768/2` for the middle of a 16 bit channel.
+fn dmx_value(value: &str) -> Option<u8> {
+    let (value, bytes) = match value.split_once('/') {
+        Some((value, bytes)) => (value.parse::<u64>().ok()?, bytes.parse::<u32>().ok()?),
+        None => (value.parse::<u64>().ok()?, 1),
+    };
+    Some((value >> (8 * bytes.saturating_sub(1))).min(255) as u8)
+}
+
+/// Parses `description.xml`.
+pub fn parse(raw: &str) -> Result<ImportReport, String> {
+    let document = Document::parse(raw).map_err(|err| err.to_string())?;
+
+    let fixture_type = document
+        .root_element()
+        .children()
+        .find(|n| n.has_tag_name("FixtureType"))
+        .ok_or("Not a GDTF description: FixtureType is missing")?;
+
+    let manufacturer = fixture_type.attribute("Manufacturer").unwrap_or("Unknown");
+    let name = fixture_type
+        .attribute("LongName")
+        .filter(|n| !n.is_empty())
+        .or_else(|| fixture_type.attribute("Name"))
+        .unwrap_or("Unknown");
+
+    // Sub-attributes like "Gobo1SelectSpin" point at their main attribute, "Gobo1".
+    let main_attributes: HashMap<&str, &str> = fixture_type
+        .descendants()
+        .filter(|n| n.has_tag_name("Attribute"))
+        .filter_map(|n| Some((n.attribute("Name")?, n.attribute("MainAttribute")?)))
+        .collect();
+
+    let mut unmapped = vec![];
+    let mut channels: Vec<ChannelDefinition> = vec![];
+    let mut modes = vec![];
+
+    for dmx_mode in fixture_type
+        .descendants()
+        .filter(|n| n.has_tag_name("DMXMode"))
+    {
+        let mode_name = dmx_mode.attribute("Name").unwrap_or("Default").to_string();
+        let mut slots: Vec<Option<String>> = vec![];
+
+        for dmx_channel in dmx_mode
+            .descendants()
+            .filter(|n| n.has_tag_name("DMXChannel"))
+        {
+            let Some(logical) = dmx_channel
+                .children()
+                .find(|n| n.has_tag_name("LogicalChannel"))
+            else {
+                continue;
+            };
+            let gdtf_attribute = logical.attribute("Attribute").unwrap_or_default();
+
+            // Virtual channels have no offset, they only exist inside the fixture.
+            let offsets: Vec<usize> = dmx_channel
+                .attribute("Offset")
+                .unwrap_or("None")
+                .split(',')
+                .filter_map(|o| o.trim().parse().ok())
+                .collect();
+            if offsets.is_empty() {
+                continue;
+            }
+
+            if dmx_channel.attribute("DMXBreak").unwrap_or("1") != "1" {
+                let entry = format!("Mode {mode_name}: {gdtf_attribute} on a second DMX break");
+                if !unmapped.contains(&entry) {
+                    unmapped.push(entry);
+                }
+                continue;
+            }
+
+            let main = main_attributes
+                .get(gdtf_attribute)
+                .copied()
+                .unwrap_or(gdtf_attribute);
+            let attribute = map_attribute(main).unwrap_or_else(|| {
+                let entry = format!("{gdtf_attribute}: no matching attribute");
+                if !unmapped.contains(&entry) {
+                    unmapped.push(entry);
+                }
+                Attribute::Generic
+            });
+
+            // Like in GDTF, channels are named after geometry and attribute, e.g. "Beam Dimmer".
+            let channel_name = match dmx_channel.attribute("Geometry") {
+                Some(geometry) => format!("{geometry} {gdtf_attribute}"),
+                None => gdtf_attribute.to_string(),
+            };
+            let fine_names: Vec<String> = (1..offsets.len())
+                .map(|byte| match byte {
+                    1 => format!("{channel_name} fine"),
+                    byte => format!("{channel_name} fine {byte}"),
+                })
+                .collect();
+
+            if !channels.iter().any(|c| c.name == channel_name) {
+                let functions: Vec<Node> = logical
+                    .children()
+                    .filter(|n| n.has_tag_name("ChannelFunction"))
+                    .collect();
+
+                // GDTF 1.0 keeps the default on the channel, later versions on the function.
+                let default_value = dmx_channel
+                    .attribute("Default")
+                    .or_else(|| functions.first()?.attribute("Default"))
+                    .and_then(dmx_value)
+                    .unwrap_or_default();
+
+                for fine in &fine_names {
+                    channels.push(ChannelDefinition {
+                        name: fine.clone(),
+                        attribute,
+                        fine_channels: vec![],
+                        default_value: 0,
+                        ranges: vec![],
+                    });
+                }
+                channels.push(ChannelDefinition {
+                    name: channel_name.clone(),
+                    attribute,
+                    fine_channels: fine_names.clone(),
+                    default_value,
+                    ranges: ranges(&functions),
+                });
+            }
+
+            for (offset, name) in offsets
+                .iter()
+                .zip([&channel_name].into_iter().chain(&fine_names))
+            {
+                let index = offset.saturating_sub(1);
+                if slots.len() <= index {
+                    slots.resize(index + 1, None);
+                }
+                slots[index] = Some(name.clone());
+            }
+        }
+
+        // Unused slots still take up an address.
+        let mode_channels = slots
+            .into_iter()
+            .enumerate()
+            .map(|(index, slot)| {
+                slot.unwrap_or_else(|| {
+                    let name = format!("Unused {}", index + 1);
+                    if !channels.iter().any(|c| c.name == name) {
+                        channels.push(ChannelDefinition {
+                            name: name.clone(),
+                            attribute: Attribute::Generic,
+                            fine_channels: vec![],
+                            default_value: 0,
+                            ranges: vec![],
+                        });
+                    }
+                    name
+                })
+            })
+            .collect();
+
+        modes.push(Mode {
+            name: mode_name,
+            channels: mode_channels,
+        });
+    }
+
+    Ok(ImportReport {
+        profile: FixtureProfile {
+            manufacturer: manufacturer.to_string(),
+            name: name.to_string(),
+            channels,
+            modes,
+        },
+        unmapped,
+    })
+}
+
+/// Maps GDTF's standard attribute names, see the attribute table of DIN SPEC 15800.
+fn map_attribute(name: &str) -> Option<Attribute> {
+    let attribute = match name {
+        "Dimmer" => Attribute::Dimmer,
+        "ColorAdd_R" | "ColorRGB_Red" => Attribute::Red,
+        "ColorAdd_G" | "ColorRGB_Green" => Attribute::Green,
+        "ColorAdd_B" | "ColorRGB_Blue" => Attribute::Blue,
+        "ColorAdd_W" | "ColorAdd_WW" | "ColorAdd_CW" => Attribute::White,
+        "ColorAdd_A" => Attribute::Amber,
+        "ColorAdd_UV" => Attribute::Uv,
+        "ColorSub_C" | "ColorAdd_C" => Attribute::Cyan,
+        "ColorSub_M" | "ColorAdd_M" => Attribute::Magenta,
+        "ColorSub_Y" | "ColorAdd_Y" => Attribute::Yellow,
+        "CTO" => Attribute::Cto,
+        "CTB" => Attribute::Ctb,
+        "Pan" => Attribute::Pan,
+        "Tilt" => Attribute::Tilt,
+        "NoFeature" => Attribute::Generic,
+        name if name.starts_with("Shutter") || name.starts_with("StrobeFrequency") => {
+            Attribute::Strobe
+        }
+        name if name.starts_with("Gobo") => Attribute::Gobo,
+        name if name.starts_with("ColorMacro") || name.starts_with("Effects") => Attribute::Macro,
+        _ => return None,
+    };
+
+    Some(attribute)
+}
+
+/// Channel functions split a channel into ranges, e.g. "Open", "Strobe" and "Pulse".
+fn ranges(functions: &[Node]) -> Vec<ChannelRange> {
+    let starts: Vec<(u8, String)> = functions
+        .iter()
+        .filter_map(|f| {
+            let from = dmx_value(f.attribute("DMXFrom")?)?;
+            let name = f.attribute("Name").unwrap_or_default().to_string();
+            Some((from, name))
+        })
+        .collect();
+
+    informative_ranges(
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, (from, name))| ChannelRange {
+                from: *from,
+                to: starts
+                    .get(i + 1)
+                    .map(|(next, _)| next.saturating_sub(1))
+                    .unwrap_or(255),
+                name: name.clone(),
+            })
+            .collect(),
+    )
+}
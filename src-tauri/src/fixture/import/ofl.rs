@@ -0,0 +1,262 @@
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+use crate::fixture::profile::{Attribute, ChannelDefinition, ChannelRange, FixtureProfile, Mode};
+
+use super::{informative_ranges, ImportReport};
+
+/// Imports an Open Fixture Library fixture file.
+///
+/// Fixtures exported from OFL name their manufacturer in `manufacturerKey`. The
+/// files in the library's own repository do not, it keeps them in one directory
+/// per manufacturer instead.
+pub fn import(path: &Path) -> Result<ImportReport, String> {
+    let raw = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    parse(&raw, manufacturer_directory(path))
+}
+
+/// The manufacturer directory of a file in the OFL repository layout, like
+/// `fixtures/cameo/flat-pro-18.json`, or "Unknown" for a file anywhere else.
+pub fn manufacturer_directory(path: &Path) -> &str {
+    let directory = path.parent();
+    let in_repository = directory
+        .and_then(|d| d.parent())
+        .and_then(|d| d.file_name())
+        .is_some_and(|n| n == "fixtures");
+
+    match in_repository {
+        true => directory
+            .and_then(|d| d.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown"),
+        false => "Unknown",
+    }
+}
+
+/// Whether the JSON is an OFL fixture rather than e.g. a Blaulicht profile.
+pub fn is_ofl(raw: &str) -> bool {
+    let Ok(fixture) = serde_json::from_str::<Value>(raw) else {
+        return false;
+    };
+    let schema = fixture["$schema"].as_str().unwrap_or_default();
+    schema.contains("open-fixture-library") || fixture.get("availableChannels").is_some()
+}
+
+/// Parses an OFL fixture, falling back to `directory` if it does not name its manufacturer.
+pub fn parse(raw: &str, directory: &str) -> Result<ImportReport, String> {
+    let fixture: Value = serde_json::from_str(raw).map_err(|err| err.to_string())?;
+    let manufacturer = fixture["manufacturerKey"].as_str().unwrap_or(directory);
+
+    let name = fixture["name"]
+        .as_str()
+        .ok_or("Not an OFL fixture: name is missing")?;
+    let available = fixture["availableChannels"]
+        .as_object()
+        .ok_or("Not an OFL fixture: availableChannels is missing")?;
+
+    let mut unmapped = vec![];
+    if fixture.get("templateChannels").is_some() {
+        unmapped.push("Template (matrix) channels".to_string());
+    }
+
+    let mut channels = vec![];
+    for (channel_name, channel) in available {
+        let fine_channels: Vec<String> = channel["fineChannelAliases"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|a| a.as_str())
+            .map(str::to_string)
+            .collect();
+
+        let capabilities: Vec<&Value> = match channel.get("capability") {
+            Some(capability) => vec![capability],
+            None => channel["capabilities"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .collect(),
+        };
+
+        let mut attribute = None;
+        let mut ranges = vec![];
+        for capability in &capabilities {
+            let kind = capability["type"].as_str().unwrap_or_default();
+            match map_capability(capability) {
+                Some(mapped) => {
+                    attribute.get_or_insert(mapped);
+                }
+                None if kind != "NoFunction" => {
+                    let entry = format!("{channel_name}: {kind}");
+                    if !unmapped.contains(&entry) {
+                        unmapped.push(entry);
+                    }
+                }
+                None => {}
+            }
+
+            if let Some(range) = dmx_range(capability, fine_channels.len()) {
+                ranges.push(range);
+            }
+        }
+
+        let attribute = attribute.unwrap_or(Attribute::Generic);
+        let default_value = default_value(&channel["defaultValue"], fine_channels.len());
+
+        for fine in &fine_channels {
+            channels.push(ChannelDefinition {
+                name: fine.clone(),
+                attribute,
+                fine_channels: vec![],
+                default_value: 0,
+                ranges: vec![],
+            });
+        }
+
+        channels.push(ChannelDefinition {
+            name: channel_name.clone(),
+            attribute,
+            fine_channels,
+            default_value,
+            ranges: informative_ranges(ranges),
+        });
+    }
+
+    let mut modes = vec![];
+    for mode in fixture["modes"].as_array().into_iter().flatten() {
+        let mode_name = mode["name"].as_str().unwrap_or("Default").to_string();
+
+        let mut mode_channels = vec![];
+        let mut supported = true;
+        for channel in mode["channels"].as_array().into_iter().flatten() {
+            match channel {
+                Value::String(name) => mode_channels.push(name.clone()),
+                // Unused slots still take up an address.
+                Value::Null => {
+                    let name = format!("Unused {}", mode_channels.len() + 1);
+                    if !channels.iter().any(|c| c.name == name) {
+                        channels.push(ChannelDefinition {
+                            name: name.clone(),
+                            attribute: Attribute::Generic,
+                            fine_channels: vec![],
+                            default_value: 0,
+                            ranges: vec![],
+                        });
+                    }
+                    mode_channels.push(name);
+                }
+                _ => supported = false,
+            }
+        }
+
+        if !supported {
+            unmapped.push(format!("Mode {mode_name}: matrix channel insert"));
+            continue;
+        }
+
+        modes.push(Mode {
+            name: mode_name,
+            channels: mode_channels,
+        });
+    }
+
+    Ok(ImportReport {
+        profile: FixtureProfile {
+            manufacturer: manufacturer.to_string(),
+            name: name.to_string(),
+            channels,
+            modes,
+        },
+        unmapped,
+    })
+}
+
+fn map_capability(capability: &Value) -> Option<Attribute> {
+    let attribute = match capability["type"].as_str()? {
+        "Intensity" => Attribute::Dimmer,
+        "ColorIntensity" => match capability["color"].as_str()? {
+            "Red" => Attribute::Red,
+            "Green" => Attribute::Green,
+            "Blue" => Attribute::Blue,
+            "White" | "Warm White" | "Cold White" => Attribute::White,
+            "Amber" => Attribute::Amber,
+            "UV" => Attribute::Uv,
+            "Cyan" => Attribute::Cyan,
+            "Magenta" => Attribute::Magenta,
+            "Yellow" => Attribute::Yellow,
+            _ => return None,
+        },
+        "ColorTemperature" => color_temperature(capability)?,
+        "ShutterStrobe" | "StrobeSpeed" | "StrobeDuration" => Attribute::Strobe,
+        "Pan" | "PanContinuous" => Attribute::Pan,
+        "Tilt" | "TiltContinuous" => Attribute::Tilt,
+        "WheelSlot" | "WheelShake" | "WheelSlotRotation" | "WheelRotation" => {
+            let wheel = capability["wheel"].as_str().unwrap_or_default();
+            if !wheel.to_lowercase().contains("gobo") {
+                return None;
+            }
+            Attribute::Gobo
+        }
+        "Effect" | "EffectSpeed" | "EffectDuration" => Attribute::Macro,
+        _ => return None,
+    };
+
+    Some(attribute)
+}
+
+/// Whether a color temperature capability warms up (CTO) or cools down (CTB) the beam,
+/// e.g. `"colorTemperatureStart": "6500K", "colorTemperatureEnd": "3200K"` is a CTO.
+fn color_temperature(capability: &Value) -> Option<Attribute> {
+    let kelvin =
+        |key: &str| -> Option<f32> { capability[key].as_str()?.trim_end_matches('K').parse().ok() };
+
+    match (
+        kelvin("colorTemperatureStart"),
+        kelvin("colorTemperatureEnd"),
+    ) {
+        (Some(start), Some(end)) if end > start => Some(Attribute::Ctb),
+        (Some(_), Some(_)) => Some(Attribute::Cto),
+        _ => None,
+    }
+}
+
+/// OFL ranges may be given in the resolution of the fine channels, they are cut down to 8 bit.
+fn dmx_range(capability: &Value, fine_channels: usize) -> Option<ChannelRange> {
+    let range = capability["dmxRange"].as_array()?;
+    let (from, to) = (range.first()?.as_u64()?, range.get(1)?.as_u64()?);
+
+    let shift = if to > 255 {
+        8 * fine_channels as u32
+    } else {
+        0
+    };
+    let name = capability["comment"]
+        .as_str()
+        .or_else(|| capability["effectName"].as_str())
+        .or_else(|| capability["type"].as_str())
+        .unwrap_or_default();
+
+    Some(ChannelRange {
+        from: (from >> shift).min(255) as u8,
+        to: (to >> shift).min(255) as u8,
+        name: name.to_string(),
+    })
+}
+
+/// Default values are either a DMX value or a percentage like "50%".
+fn default_value(value: &Value, fine_channels: usize) -> u8 {
+    match value {
+        Value::Number(n) => {
+            let n = n.as_u64().unwrap_or_default();
+            let shift = if n > 255 { 8 * fine_channels as u32 } else { 0 };
+            (n >> shift).min(255) as u8
+        }
+        Value::String(s) => s
+            .trim_end_matches('%')
+            .parse::<f32>()
+            .map(|p| (p / 100.0 * 255.0).round().clamp(0.0, 255.0) as u8)
+            .unwrap_or_default(),
+        _ => 0,
+    }
+}
@@ -0,0 +1,250 @@
+use std::{fs, path::Path};
+
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::fixture::profile::{Attribute, ChannelDefinition, ChannelRange, FixtureProfile, Mode};
+
+use super::{informative_ranges, ImportReport};
+
+/// Imports a QLC+ fixture definition (`.qxf`).
+pub fn import(path: &Path) -> Result<ImportReport, String> {
+    let raw = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    parse(&raw)
+}
+
+/// What a QLC+ channel maps to, and whether it carries the fine byte.
+struct Mapping {
+    attribute: Option<Attribute>,
+    fine: bool,
+}
+
+pub fn parse(raw: &str) -> Result<ImportReport, String> {
+    // Every .qxf starts with `<!DOCTYPE FixtureDefinition>`.
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(raw, options).map_err(|err| err.to_string())?;
+
+    let root = document.root_element();
+    if !root.has_tag_name("FixtureDefinition") {
+        return Err("Not a QLC+ fixture definition".to_string());
+    }
+
+    let text_of = |name: &str| {
+        child(root, name)
+            .and_then(|n| n.text())
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let manufacturer = text_of("Manufacturer");
+    let model = text_of("Model");
+
+    let mut unmapped = vec![];
+    let mut channels: Vec<(ChannelDefinition, bool)> = vec![];
+
+    for channel in root.children().filter(|n| n.has_tag_name("Channel")) {
+        let name = channel
+            .attribute("Name")
+            .ok_or("Channel without a name")?
+            .to_string();
+
+        let mapping = map_channel(channel);
+        if mapping.attribute.is_none() {
+            unmapped.push(format!("{name}: {}", describe(channel)));
+        }
+
+        let capabilities: Vec<Node> = children(channel, "Capability").collect();
+        let ranges = informative_ranges(
+            capabilities
+                .iter()
+                .filter_map(|capability| {
+                    Some(ChannelRange {
+                        from: capability.attribute("Min")?.parse().ok()?,
+                        to: capability.attribute("Max")?.parse().ok()?,
+                        name: capability.text().unwrap_or_default().trim().to_string(),
+                    })
+                })
+                .collect(),
+        );
+
+        channels.push((
+            ChannelDefinition {
+                name,
+                attribute: mapping.attribute.unwrap_or(Attribute::Generic),
+                fine_channels: vec![],
+                default_value: channel
+                    .attribute("Default")
+                    .and_then(|d| d.parse().ok())
+                    .unwrap_or_default(),
+                ranges,
+            },
+            mapping.fine,
+        ));
+    }
+
+    link_fine_channels(&mut channels);
+    let channels: Vec<ChannelDefinition> = channels.into_iter().map(|(c, _)| c).collect();
+
+    let mut modes = vec![];
+    for mode in children(root, "Mode") {
+        let name = mode.attribute("Name").unwrap_or("Default").to_string();
+
+        let mut numbered: Vec<(u16, String)> = children(mode, "Channel")
+            .filter_map(|c| {
+                let number = c.attribute("Number")?.parse().ok()?;
+                Some((number, c.text()?.trim().to_string()))
+            })
+            .collect();
+        numbered.sort_by_key(|(number, _)| *number);
+
+        let heads = children(mode, "Head").count();
+        if heads > 1 {
+            unmapped.push(format!(
+                "Mode {name}: {heads} heads, imported as one fixture"
+            ));
+        }
+
+        modes.push(Mode {
+            name,
+            channels: numbered.into_iter().map(|(_, c)| c).collect(),
+        });
+    }
+
+    Ok(ImportReport {
+        profile: FixtureProfile {
+            manufacturer,
+            name: model,
+            channels,
+            modes,
+        },
+        unmapped,
+    })
+}
+
+fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn children<'a, 'i: 'a>(
+    node: Node<'a, 'i>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'i>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+/// The preset (QLC+ 4.12 and later) or group a channel was declared with, for the report.
+fn describe(channel: Node) -> String {
+    if let Some(preset) = channel.attribute("Preset") {
+        return preset.to_string();
+    }
+
+    let group = child(channel, "Group")
+        .and_then(|g| g.text())
+        .unwrap_or("no group");
+    match child(channel, "Colour").and_then(|c| c.text()) {
+        Some(colour) => format!("{group} ({colour})"),
+        None => group.to_string(),
+    }
+}
+
+fn map_channel(channel: Node) -> Mapping {
+    match channel.attribute("Preset") {
+        Some(preset) => map_preset(preset),
+        None => map_group(channel),
+    }
+}
+
+fn map_preset(preset: &str) -> Mapping {
+    let fine = preset.ends_with("Fine");
+    let attribute = match preset.trim_end_matches("Fine") {
+        "IntensityMasterDimmer" | "IntensityDimmer" => Some(Attribute::Dimmer),
+        "IntensityRed" => Some(Attribute::Red),
+        "IntensityGreen" => Some(Attribute::Green),
+        "IntensityBlue" => Some(Attribute::Blue),
+        "IntensityWhite" => Some(Attribute::White),
+        "IntensityAmber" => Some(Attribute::Amber),
+        "IntensityUV" => Some(Attribute::Uv),
+        "IntensityCyan" => Some(Attribute::Cyan),
+        "IntensityMagenta" => Some(Attribute::Magenta),
+        "IntensityYellow" => Some(Attribute::Yellow),
+        "ColorCTOMixer" => Some(Attribute::Cto),
+        "ColorCTBMixer" => Some(Attribute::Ctb),
+        "PositionPan" => Some(Attribute::Pan),
+        "PositionTilt" => Some(Attribute::Tilt),
+        "ShutterStrobeSlowFast" | "ShutterStrobeFastSlow" => Some(Attribute::Strobe),
+        "GoboWheel" | "GoboIndex" => Some(Attribute::Gobo),
+        "ColorMacro" => Some(Attribute::Macro),
+        "NoFunction" => Some(Attribute::Generic),
+        _ => None,
+    };
+
+    Mapping { attribute, fine }
+}
+
+/// Channels of QLC+ before 4.12 only declare a group, intensity channels also a colour.
+fn map_group(channel: Node) -> Mapping {
+    let Some(group) = child(channel, "Group") else {
+        return Mapping {
+            attribute: None,
+            fine: false,
+        };
+    };
+
+    let fine = group.attribute("Byte") == Some("1");
+    let attribute = match group.text().unwrap_or_default().trim() {
+        "Intensity" => match child(channel, "Colour").and_then(|c| c.text()) {
+            None | Some("Generic") => Some(Attribute::Dimmer),
+            Some("Red") => Some(Attribute::Red),
+            Some("Green") => Some(Attribute::Green),
+            Some("Blue") => Some(Attribute::Blue),
+            Some("White") => Some(Attribute::White),
+            Some("Amber") => Some(Attribute::Amber),
+            Some("UV") => Some(Attribute::Uv),
+            Some("Cyan") => Some(Attribute::Cyan),
+            Some("Magenta") => Some(Attribute::Magenta),
+            Some("Yellow") => Some(Attribute::Yellow),
+            Some(_) => None,
+        },
+        "Pan" => Some(Attribute::Pan),
+        "Tilt" => Some(Attribute::Tilt),
+        "Shutter" => Some(Attribute::Strobe),
+        "Gobo" => Some(Attribute::Gobo),
+        "Effect" => Some(Attribute::Macro),
+        "Nothing" => Some(Attribute::Generic),
+        _ => None,
+    };
+
+    Mapping { attribute, fine }
+}
+
+/// QLC+ does not link fine channels to their coarse channel. A fine channel is attached
+/// to the coarse channel of the same attribute whose name it starts with, e.g. "Pan fine"
+/// to "Pan", or else to the first coarse channel of that attribute without a fine channel.
+fn link_fine_channels(channels: &mut [(ChannelDefinition, bool)]) {
+    let fine: Vec<(String, Attribute)> = channels
+        .iter()
+        .filter(|(c, fine)| *fine && c.attribute != Attribute::Generic)
+        .map(|(c, _)| (c.name.clone(), c.attribute))
+        .collect();
+
+    for (name, attribute) in fine {
+        let candidates = || {
+            channels
+                .iter()
+                .position(|(c, fine)| {
+                    !fine && c.attribute == attribute && name.starts_with(&c.name)
+                })
+                .or_else(|| {
+                    channels.iter().position(|(c, fine)| {
+                        !fine && c.attribute == attribute && c.fine_channels.is_empty()
+                    })
+                })
+        };
+
+        if let Some(coarse) = candidates() {
+            channels[coarse].0.fine_channels.push(name);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::Frame;
+
+use super::{patch::ResolvedFixture, profile::Attribute};
+
+/// How a moving head is hung and how far it may move. All angles are in degrees
+/// from the center position.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Movement {
+    /// Full travel of the pan channel, e.g. 540.
+    pub pan_range: f32,
+    /// Full travel of the tilt channel, e.g. 270.
+    pub tilt_range: f32,
+    pub invert_pan: bool,
+    pub invert_tilt: bool,
+    /// Pan goes to the tilt channel and vice versa, e.g. for heads hung sideways.
+    pub swap: bool,
+    /// Soft limits in the fixture's own axes, after swapping and inverting.
+    /// The head never goes beyond them, e.g. to keep it from pointing into the audience.
+    pub pan_min: f32,
+    pub pan_max: f32,
+    pub tilt_min: f32,
+    pub tilt_max: f32,
+}
+
+impl Default for Movement {
+    fn default() -> Self {
+        Self {
+            pan_range: 540.0,
+            tilt_range: 270.0,
+            invert_pan: false,
+            invert_tilt: false,
+            swap: false,
+            pan_min: -270.0,
+            pan_max: 270.0,
+            tilt_min: -135.0,
+            tilt_max: 135.0,
+        }
+    }
+}
+
+/// Converts an angle into a channel value from 0.0 to 1.0, 0.5 being the center.
+fn channel_value(degrees: f32, invert: bool, min: f32, max: f32, range: f32) -> f32 {
+    let degrees = if invert { -degrees } else { degrees };
+    // Not `clamp`, which panics on limits the wrong way round.
+    let degrees = degrees.max(min).min(max);
+
+    match range > 0.0 {
+        true => (degrees / range + 0.5).clamp(0.0, 1.0),
+        false => 0.5,
+    }
+}
+
+impl Movement {
+    /// Pan and tilt channel values, from 0.0 to 1.0, for a direction in degrees.
+    pub fn channel_values(&self, pan: f32, tilt: f32) -> (f32, f32) {
+        let (pan, tilt) = match self.swap {
+            true => (tilt, pan),
+            false => (pan, tilt),
+        };
+
+        (
+            channel_value(
+                pan,
+                self.invert_pan,
+                self.pan_min,
+                self.pan_max,
+                self.pan_range,
+            ),
+            channel_value(
+                tilt,
+                self.invert_tilt,
+                self.tilt_min,
+                self.tilt_max,
+                self.tilt_range,
+            ),
+        )
+    }
+}
+
+/// Points the fixture in a direction given in degrees, using the fine channels if the
+/// mode has them.
+pub fn write(fixture: &ResolvedFixture, frames: &mut [Frame], pan: f32, tilt: f32) {
+    let (pan, tilt) = fixture.movement.channel_values(pan, tilt);
+    fixture.write(frames, Attribute::Pan, pan);
+    fixture.write(frames, Attribute::Tilt, tilt);
+}
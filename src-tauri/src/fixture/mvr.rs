@@ -0,0 +1,490 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{Cursor, Read, Seek, Write},
+    path::Path,
+};
+
+use roxmltree::{Document, Node};
+use serde::Serialize;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use crate::dmx::UNIVERSE_SIZE;
+
+use super::{
+    curve::IntensityCurve,
+    gdtf_path,
+    import::{gdtf, ImportReport},
+    movement::Movement,
+    patch::{PatchedFixture, Position},
+    profile::{Attribute, FixtureProfile, Mode},
+};
+
+/// Name of the scene description inside an MVR archive.
+const SCENE_FILE: &str = "GeneralSceneDescription.xml";
+
+/// A GDTF file referenced by an MVR file.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GdtfFile {
+    /// File name inside the MVR archive, e.g. `Robe_Robin_LEDBeam_150.gdtf`.
+    pub spec: String,
+    #[serde(flatten)]
+    pub report: ImportReport,
+    /// The archive itself, kept next to the profile for a later export.
+    #[serde(skip)]
+    pub raw: Vec<u8>,
+}
+
+/// A rig read from an MVR file.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rig {
+    pub patch: Vec<PatchedFixture>,
+    pub gdtf_files: Vec<GdtfFile>,
+    /// Fixtures that were skipped or changed, e.g. "Spot 3: mode Extended not found, using Basic".
+    pub warnings: Vec<String>,
+}
+
+/// Imports the fixtures of an MVR file together with the GDTF files they reference.
+pub fn import(path: &Path) -> Result<Rig, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|err| err.to_string())?;
+
+    let raw = read_file(&mut archive, SCENE_FILE)?;
+    let raw = String::from_utf8(raw).map_err(|err| format!("{SCENE_FILE}: {err}"))?;
+    let document = Document::parse(&raw).map_err(|err| format!("{SCENE_FILE}: {err}"))?;
+
+    let mut rig = Rig {
+        patch: vec![],
+        gdtf_files: vec![],
+        warnings: vec![],
+    };
+
+    // Fixture ids are optional in MVR, missing and duplicate ones are handed out afterwards.
+    let mut without_id = vec![];
+
+    for node in document.descendants().filter(|n| n.has_tag_name("Fixture")) {
+        let name = node.attribute("name").unwrap_or("Fixture").to_string();
+
+        let Some(spec) = text(node, "GDTFSpec") else {
+            rig.warnings.push(format!("{name}: no GDTF file, skipped"));
+            continue;
+        };
+        // The extension is optional as well.
+        let spec = match spec.ends_with(".gdtf") {
+            true => spec.to_string(),
+            false => format!("{spec}.gdtf"),
+        };
+
+        if !rig.gdtf_files.iter().any(|g| g.spec == spec) {
+            match read_gdtf(&mut archive, &spec) {
+                Ok(gdtf) => rig.gdtf_files.push(gdtf),
+                Err(err) => rig.warnings.push(format!("{spec}: {err}")),
+            }
+        }
+        let Some(gdtf) = rig.gdtf_files.iter().find(|g| g.spec == spec) else {
+            rig.warnings
+                .push(format!("{name}: {spec} is unusable, skipped"));
+            continue;
+        };
+        let profile = &gdtf.report.profile;
+
+        let requested = text(node, "GDTFMode").unwrap_or_default();
+        let mode = match profile.mode(requested) {
+            Some(mode) => mode.name.clone(),
+            None => {
+                let Some(first) = profile.modes.first() else {
+                    rig.warnings
+                        .push(format!("{name}: {spec} has no modes, skipped"));
+                    continue;
+                };
+                rig.warnings.push(format!(
+                    "{name}: mode {requested} not found, using {}",
+                    first.name
+                ));
+                first.name.clone()
+            }
+        };
+
+        let Some((universe, address)) = node
+            .descendants()
+            .find(|n| n.has_tag_name("Address"))
+            .and_then(|a| a.text())
+            .and_then(parse_address)
+        else {
+            rig.warnings
+                .push(format!("{name}: no DMX address, skipped"));
+            continue;
+        };
+
+        let id = text(node, "FixtureID").and_then(|id| id.parse::<u32>().ok());
+        let fixture = PatchedFixture {
+            id: id.unwrap_or_default(),
+            name,
+            profile: profile.id(),
+            mode,
+            universe,
+            address,
+            position: text(node, "Matrix").and_then(parse_translation),
+            movement: Movement::default(),
+            intensity_curve: IntensityCurve::default(),
+        };
+
+        match id {
+            Some(id) if !rig.patch.iter().any(|f| f.id == id) => rig.patch.push(fixture),
+            _ => without_id.push(fixture),
+        }
+    }
+
+    for mut fixture in without_id {
+        fixture.id = (1..=u32::MAX)
+            .find(|id| !rig.patch.iter().any(|f| f.id == *id))
+            .ok_or("No fixture id left")?;
+        rig.patch.push(fixture);
+    }
+
+    Ok(rig)
+}
+
+fn read_file<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>, String> {
+    let mut file = archive
+        .by_name(name)
+        .map_err(|err| format!("{name}: {err}"))?;
+
+    let mut raw = vec![];
+    file.read_to_end(&mut raw).map_err(|err| err.to_string())?;
+    Ok(raw)
+}
+
+fn read_gdtf<R: Read + Seek>(archive: &mut ZipArchive<R>, spec: &str) -> Result<GdtfFile, String> {
+    let raw = read_file(archive, spec)?;
+    let report = gdtf::read(Cursor::new(&raw))?;
+    report.profile.validate()?;
+
+    Ok(GdtfFile {
+        spec: spec.to_string(),
+        report,
+        raw,
+    })
+}
+
+fn text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|n| n.has_tag_name(name))?
+        .text()
+        .map(str::trim)
+}
+
+/// MVR addresses are either absolute, like `513` for the first channel of the second
+/// universe, or `universe.address`, both starting at 1.
+fn parse_address(address: &str) -> Option<(u16, u16)> {
+    let (universe, address) = match address.trim().split_once('.') {
+        Some((universe, address)) => (universe.parse::<u16>().ok()?, address.parse().ok()?),
+        None => {
+            let absolute = address.trim().parse::<usize>().ok()?.checked_sub(1)?;
+            (
+                (absolute / UNIVERSE_SIZE + 1) as u16,
+                (absolute % UNIVERSE_SIZE + 1) as u16,
+            )
+        }
+    };
+
+    Some((universe.checked_sub(1)?, address))
+}
+
+/// A matrix like `{1,0,0}{0,1,0}{0,0,1}{2000,-500,4500}`, the last row being the translation.
+///
+/// Translations of the layer and group objects a fixture is nested in are not added.
+fn parse_translation(matrix: &str) -> Option<Position> {
+    let row = matrix
+        .split('}')
+        .map(|row| row.trim().trim_start_matches('{'))
+        .filter(|row| !row.is_empty())
+        .nth(3)?;
+
+    let values: Vec<f32> = row
+        .split(',')
+        .map(|v| v.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    match values[..] {
+        [x, y, z] => Some(Position { x, y, z }),
+        _ => None,
+    }
+}
+
+/// Writes the patch as an MVR file. Profiles imported from GDTF are exported with their
+/// original file, others as a GDTF file generated from the profile.
+pub fn export(
+    path: &Path,
+    patch: &[PatchedFixture],
+    profiles: &[FixtureProfile],
+    library: &Path,
+) -> Result<(), String> {
+    let mut specs: HashMap<String, String> = HashMap::new();
+    let mut gdtf_files: Vec<(String, Vec<u8>)> = vec![];
+
+    for fixture in patch {
+        if specs.contains_key(&fixture.profile) {
+            continue;
+        }
+        let Some(profile) = profiles.iter().find(|p| p.id() == fixture.profile) else {
+            eprintln!("[fixture] {}: unknown profile, not exported", fixture.name);
+            continue;
+        };
+
+        let original = gdtf_path(library, profile);
+        let spec = unique_spec(&gdtf_files, &original);
+        let raw = match fs::read(&original) {
+            Ok(raw) => raw,
+            Err(_) => generate_gdtf(profile)?,
+        };
+
+        specs.insert(fixture.profile.clone(), spec.clone());
+        gdtf_files.push((spec, raw));
+    }
+
+    let mut fixtures = String::new();
+    for fixture in patch {
+        let Some(spec) = specs.get(&fixture.profile) else {
+            continue;
+        };
+        let position = fixture.position.unwrap_or(Position {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        });
+        let absolute = fixture.universe as usize * UNIVERSE_SIZE + fixture.address as usize;
+
+        fixtures.push_str(&format!(
+            r#"
+        <Fixture name="{name}" uuid="{uuid}">
+          <Matrix>{{1,0,0}}{{0,1,0}}{{0,0,1}}{{{x},{y},{z}}}</Matrix>
+          <GDTFSpec>{spec}</GDTFSpec>
+          <GDTFMode>{mode}</GDTFMode>
+          <Addresses>
+            <Address break="0">{absolute}</Address>
+          </Addresses>
+          <FixtureID>{id}</FixtureID>
+          <UnitNumber>0</UnitNumber>
+        </Fixture>"#,
+            name = escape(&fixture.name),
+            uuid = uuid(),
+            x = position.x,
+            y = position.y,
+            z = position.z,
+            spec = escape(spec),
+            mode = escape(&fixture.mode),
+            id = fixture.id,
+        ));
+    }
+
+    let scene = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no" ?>
+<GeneralSceneDescription verMajor="1" verMinor="6" provider="Blaulicht" providerVersion="{version}">
+  <UserData/>
+  <Scene>
+    <Layers>
+      <Layer name="Blaulicht" uuid="{uuid}">
+        <ChildList>{fixtures}
+        </ChildList>
+      </Layer>
+    </Layers>
+  </Scene>
+</GeneralSceneDescription>
+"#,
+        version = env!("CARGO_PKG_VERSION"),
+        uuid = uuid(),
+    );
+
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut archive = ZipWriter::new(file);
+    add_file(&mut archive, SCENE_FILE, scene.as_bytes())?;
+    for (spec, raw) in &gdtf_files {
+        add_file(&mut archive, spec, raw)?;
+    }
+    archive.finish().map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+/// The file name of the GDTF file, numbered if another profile id slugs to the same name.
+fn unique_spec(gdtf_files: &[(String, Vec<u8>)], original: &Path) -> String {
+    let stem = original
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("fixture");
+    let taken = |spec: &str| gdtf_files.iter().any(|(s, _)| s == spec);
+
+    let mut spec = format!("{stem}.gdtf");
+    let mut number = 2;
+    while taken(&spec) {
+        spec = format!("{stem}-{number}.gdtf");
+        number += 1;
+    }
+    spec
+}
+
+fn add_file<W: Write + Seek>(
+    archive: &mut ZipWriter<W>,
+    name: &str,
+    raw: &[u8],
+) -> Result<(), String> {
+    archive
+        .start_file(name, SimpleFileOptions::default())
+        .map_err(|err| err.to_string())?;
+    archive.write_all(raw).map_err(|err| err.to_string())
+}
+
+/// A minimal GDTF file with one geometry per channel, enough to carry the DMX modes.
+fn generate_gdtf(profile: &FixtureProfile) -> Result<Vec<u8>, String> {
+    let geometries: String = profile
+        .channels
+        .iter()
+        .map(|c| {
+            format!(
+                "\n        <Geometry Name=\"{}\" Model=\"\"/>",
+                escape(&c.name)
+            )
+        })
+        .collect();
+    let modes: String = profile.modes.iter().map(|m| dmx_mode(profile, m)).collect();
+
+    let description = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no" ?>
+<GDTF DataVersion="1.2">
+  <FixtureType Name="{name}" ShortName="{name}" LongName="{name}" Manufacturer="{manufacturer}" Description="Exported by Blaulicht" FixtureTypeID="{uuid}" Thumbnail="" RefFT="">
+    <AttributeDefinitions/>
+    <Wheels/>
+    <PhysicalDescriptions/>
+    <Models/>
+    <Geometries>
+      <Geometry Name="Body" Model="">{geometries}
+      </Geometry>
+    </Geometries>
+    <DMXModes>{modes}
+    </DMXModes>
+  </FixtureType>
+</GDTF>
+"#,
+        name = escape(&profile.name),
+        manufacturer = escape(&profile.manufacturer),
+        uuid = uuid(),
+    );
+
+    let mut archive = ZipWriter::new(Cursor::new(vec![]));
+    add_file(&mut archive, "description.xml", description.as_bytes())?;
+    let raw = archive.finish().map_err(|err| err.to_string())?;
+    Ok(raw.into_inner())
+}
+
+fn dmx_mode(profile: &FixtureProfile, mode: &Mode) -> String {
+    let offset_of = |name: &str| mode.channels.iter().position(|c| c == name).map(|i| i + 1);
+
+    let mut channels = String::new();
+    for (index, name) in mode.channels.iter().enumerate() {
+        let Some(channel) = profile.channel(name) else {
+            continue;
+        };
+        // Fine channels are part of the offsets of their coarse channel.
+        if profile
+            .channels
+            .iter()
+            .any(|c| c.fine_channels.contains(name))
+        {
+            continue;
+        }
+
+        let offsets: Vec<String> = std::iter::once(Some(index + 1))
+            .chain(channel.fine_channels.iter().map(|f| offset_of(f)))
+            .map_while(|o| o.map(|o| o.to_string()))
+            .collect();
+
+        let attribute = gdtf_attribute(channel.attribute);
+        let functions: String = match channel.ranges.is_empty() {
+            true => format!(
+                "\n              <ChannelFunction Name=\"{attribute}\" Attribute=\"{attribute}\" DMXFrom=\"0/1\" Default=\"{}/1\"/>",
+                channel.default_value
+            ),
+            false => channel
+                .ranges
+                .iter()
+                .map(|r| {
+                    format!(
+                        "\n              <ChannelFunction Name=\"{}\" Attribute=\"{attribute}\" DMXFrom=\"{}/1\" Default=\"{}/1\"/>",
+                        escape(&r.name),
+                        r.from,
+                        channel.default_value
+                    )
+                })
+                .collect(),
+        };
+
+        channels.push_str(&format!(
+            r#"
+          <DMXChannel DMXBreak="1" Offset="{offsets}" Highlight="None" Geometry="{geometry}">
+            <LogicalChannel Attribute="{attribute}">{functions}
+            </LogicalChannel>
+          </DMXChannel>"#,
+            offsets = offsets.join(","),
+            geometry = escape(name),
+        ));
+    }
+
+    format!(
+        r#"
+      <DMXMode Name="{name}" Geometry="Body">
+        <DMXChannels>{channels}
+        </DMXChannels>
+      </DMXMode>"#,
+        name = escape(&mode.name),
+    )
+}
+
+/// The GDTF attribute Blaulicht's attributes are imported from, see [`gdtf`].
+fn gdtf_attribute(attribute: Attribute) -> &'static str {
+    match attribute {
+        Attribute::Dimmer => "Dimmer",
+        Attribute::Red => "ColorAdd_R",
+        Attribute::Green => "ColorAdd_G",
+        Attribute::Blue => "ColorAdd_B",
+        Attribute::White => "ColorAdd_W",
+        Attribute::Amber => "ColorAdd_A",
+        Attribute::Uv => "ColorAdd_UV",
+        Attribute::Cyan => "ColorSub_C",
+        Attribute::Magenta => "ColorSub_M",
+        Attribute::Yellow => "ColorSub_Y",
+        Attribute::Cto => "CTO",
+        Attribute::Ctb => "CTB",
+        Attribute::Strobe => "Shutter1",
+        Attribute::Pan => "Pan",
+        Attribute::Tilt => "Tilt",
+        Attribute::Gobo => "Gobo1",
+        Attribute::Macro => "ColorMacro1",
+        Attribute::Generic => "NoFeature",
+    }
+}
+
+fn escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A random (version 4) UUID, MVR identifies every object by one.
+fn uuid() -> String {
+    let bytes = fastrand::u128(..).to_be_bytes();
+    let mut hex: Vec<String> = bytes.iter().map(|b| format!("{b:02X}")).collect();
+    hex[6] = format!("{:02X}", bytes[6] & 0x0f | 0x40);
+    hex[8] = format!("{:02X}", bytes[8] & 0x3f | 0x80);
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        hex[0..4].concat(),
+        hex[4..6].concat(),
+        hex[6..8].concat(),
+        hex[8..10].concat(),
+        hex[10..16].concat()
+    )
+}
@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dmx::{Frame, MAX_UNIVERSES, UNIVERSE_SIZE};
+
+use super::{
+    curve::IntensityCurve,
+    movement::Movement,
+    profile::{encode, Attribute, AttributeChannels, FixtureProfile},
+};
+
+/// One fixture instance in the rig.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchedFixture {
+    /// Unique within the patch, groups refer to fixtures by id.
+    pub id: u32,
+    pub name: String,
+    /// See [`FixtureProfile::id`].
+    pub profile: String,
+    pub mode: String,
+    pub universe: u16,
+    /// First channel of the fixture, starting at 1.
+    pub address: u16,
+    #[serde(default)]
+    pub position: Option<Position>,
+    /// Only matters for fixtures with pan and tilt.
+    #[serde(default)]
+    pub movement: Movement,
+    #[serde(default)]
+    pub intensity_curve: IntensityCurve,
+}
+
+/// Where a fixture hangs in the venue, in millimeters.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Problems found by [`validate`]. Fixtures with an issue other than an overlap are not output.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PatchIssue {
+    DuplicateId {
+        id: u32,
+    },
+    UnknownProfile {
+        id: u32,
+        profile: String,
+    },
+    UnknownMode {
+        id: u32,
+        mode: String,
+    },
+    OutOfRange {
+        id: u32,
+        address: u16,
+        footprint: u16,
+    },
+    /// The universe is not below [`MAX_UNIVERSES`].
+    UniverseOutOfRange {
+        id: u32,
+        universe: u16,
+    },
+    /// The footprints of two fixtures in the same universe share channels.
+    Overlap {
+        id: u32,
+        other: u32,
+    },
+}
+
+/// A patched fixture with its attributes resolved to absolute channels.
+#[derive(Debug, Clone)]
+pub struct ResolvedFixture {
+    pub id: u32,
+    pub universe: u16,
+    /// First channel of the fixture, starting at 1.
+    pub address: u16,
+    pub footprint: u16,
+    pub attributes: Vec<AttributeChannels>,
+    pub movement: Movement,
+    pub intensity_curve: IntensityCurve,
+}
+
+/// Emitters carry the intensity of fixtures without a dimmer.
+const EMITTERS: [Attribute; 6] = [
+    Attribute::Red,
+    Attribute::Green,
+    Attribute::Blue,
+    Attribute::White,
+    Attribute::Amber,
+    Attribute::Uv,
+];
+
+impl ResolvedFixture {
+    fn indices<'a>(&self, channels: &'a AttributeChannels) -> impl Iterator<Item = usize> + 'a {
+        let first = self.address as usize - 1;
+        channels.offsets.iter().map(move |&o| first + o as usize)
+    }
+
+    /// Indices into the frame of every channel carrying the attribute, fine channels included.
+    pub fn indices_of(&self, attribute: Attribute) -> impl Iterator<Item = usize> + '_ {
+        self.attributes
+            .iter()
+            .filter(move |a| a.attribute == attribute)
+            .flat_map(|channels| self.indices(channels))
+    }
+
+    pub fn has(&self, attribute: Attribute) -> bool {
+        self.attributes.iter().any(|a| a.attribute == attribute)
+    }
+
+    /// The dimmer, or the emitters if the fixture has no dimmer.
+    pub fn is_intensity(&self, attribute: Attribute) -> bool {
+        match self.has(Attribute::Dimmer) {
+            true => attribute == Attribute::Dimmer,
+            false => EMITTERS.contains(&attribute),
+        }
+    }
+
+    /// Writes a value from 0.0 to 1.0 to every channel carrying the attribute,
+    /// using the fine channels if the mode has them. The dimmer goes through the
+    /// fixture's intensity curve, emitters are written as they are: without a dimmer,
+    /// [`crate::fixture::color::mix`] applies the curve to the intensity instead.
+    pub fn write(&self, frames: &mut [Frame], attribute: Attribute, value: f32) {
+        let Some(frame) = frames.get_mut(self.universe as usize) else {
+            return;
+        };
+        let value = match attribute {
+            Attribute::Dimmer => self.intensity_curve.apply(value),
+            _ => value,
+        };
+
+        for channels in self.attributes.iter().filter(|a| a.attribute == attribute) {
+            let mut bytes = [0; 4];
+            let bytes = &mut bytes[..channels.offsets.len().min(4)];
+            encode(value, bytes);
+
+            for (index, byte) in self.indices(channels).zip(bytes.iter()) {
+                frame[index] = *byte;
+            }
+        }
+    }
+
+    /// Resets every channel of the fixture to the default value of its attribute.
+    pub fn write_defaults(&self, frames: &mut [Frame]) {
+        let Some(frame) = frames.get_mut(self.universe as usize) else {
+            return;
+        };
+
+        for channels in &self.attributes {
+            for index in self.indices(channels) {
+                frame[index] = channels.default_value;
+            }
+        }
+    }
+
+    /// Channels (starting at 1) of the given attribute, fine channels included.
+    pub fn channels_of(&self, attribute: Attribute) -> Vec<u16> {
+        self.indices_of(attribute).map(|i| i as u16 + 1).collect()
+    }
+}
+
+fn footprint(fixture: &PatchedFixture, profiles: &[FixtureProfile]) -> Option<u16> {
+    let profile = profiles.iter().find(|p| p.id() == fixture.profile)?;
+    Some(profile.mode(&fixture.mode)?.footprint())
+}
+
+fn in_range(address: u16, footprint: u16) -> bool {
+    address >= 1 && address as usize + footprint as usize - 1 <= UNIVERSE_SIZE
+}
+
+pub fn validate(patch: &[PatchedFixture], profiles: &[FixtureProfile]) -> Vec<PatchIssue> {
+    let mut issues = vec![];
+
+    for (i, fixture) in patch.iter().enumerate() {
+        if patch[..i].iter().any(|f| f.id == fixture.id) {
+            issues.push(PatchIssue::DuplicateId { id: fixture.id });
+        }
+        if fixture.universe >= MAX_UNIVERSES {
+            issues.push(PatchIssue::UniverseOutOfRange {
+                id: fixture.id,
+                universe: fixture.universe,
+            });
+        }
+
+        let Some(profile) = profiles.iter().find(|p| p.id() == fixture.profile) else {
+            issues.push(PatchIssue::UnknownProfile {
+                id: fixture.id,
+                profile: fixture.profile.clone(),
+            });
+            continue;
+        };
+
+        let Some(mode) = profile.mode(&fixture.mode) else {
+            issues.push(PatchIssue::UnknownMode {
+                id: fixture.id,
+                mode: fixture.mode.clone(),
+            });
+            continue;
+        };
+
+        if !in_range(fixture.address, mode.footprint()) {
+            issues.push(PatchIssue::OutOfRange {
+                id: fixture.id,
+                address: fixture.address,
+                footprint: mode.footprint(),
+            });
+        }
+    }
+
+    // Sorting by address lets every fixture only be compared to its successors.
+    let mut spans: BTreeMap<u16, Vec<(u16, u16, u32)>> = BTreeMap::new();
+    for fixture in patch {
+        if let Some(footprint) = footprint(fixture, profiles) {
+            let last = fixture.address.saturating_add(footprint.max(1) - 1);
+            spans
+                .entry(fixture.universe)
+                .or_default()
+                .push((fixture.address, last, fixture.id));
+        }
+    }
+
+    for spans in spans.values_mut() {
+        spans.sort_unstable();
+        for (i, &(_, last, id)) in spans.iter().enumerate() {
+            for &(_, _, other) in spans[i + 1..]
+                .iter()
+                .take_while(|(first, ..)| *first <= last)
+            {
+                issues.push(PatchIssue::Overlap { id, other });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Resolves every fixture that can be output. Fixtures with an unknown profile or mode,
+/// whose footprint does not fit into the universe, in a universe beyond [`MAX_UNIVERSES`]
+/// or with the id of an earlier fixture are left out.
+pub fn resolve(patch: &[PatchedFixture], profiles: &[FixtureProfile]) -> Vec<ResolvedFixture> {
+    patch
+        .iter()
+        .enumerate()
+        .filter_map(|(i, fixture)| {
+            // Effects address fixtures by id, the first fixture keeps it.
+            if patch[..i].iter().any(|f| f.id == fixture.id) || fixture.universe >= MAX_UNIVERSES {
+                return None;
+            }
+            let profile = profiles.iter().find(|p| p.id() == fixture.profile)?;
+            let mode = profile.mode(&fixture.mode)?;
+            if !in_range(fixture.address, mode.footprint()) {
+                return None;
+            }
+
+            Some(ResolvedFixture {
+                id: fixture.id,
+                universe: fixture.universe,
+                address: fixture.address,
+                footprint: mode.footprint(),
+                attributes: profile.attributes(mode),
+                movement: fixture.movement,
+                intensity_curve: fixture.intensity_curve.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Intensity channels of every universe, for the grand master and blackout.
+///
+/// Fixtures without a dimmer are dimmed through their emitters instead. Fine channels are
+/// part of it, a blackout must not leave the low byte of a 16 bit dimmer lit.
+pub fn intensity_channels(fixtures: &[ResolvedFixture]) -> BTreeMap<u16, Vec<u16>> {
+    let mut channels: BTreeMap<u16, Vec<u16>> = BTreeMap::new();
+    for fixture in fixtures {
+        let universe = channels.entry(fixture.universe).or_default();
+        for attribute in [Attribute::Dimmer].iter().chain(&EMITTERS) {
+            if fixture.is_intensity(*attribute) {
+                universe.extend(fixture.channels_of(*attribute));
+            }
+        }
+    }
+    channels
+}
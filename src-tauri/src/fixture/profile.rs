@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+
+/// What a channel controls.
+/// Effects write attributes, the patch decides which channels they end up on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Attribute {
+    Dimmer,
+    Red,
+    Green,
+    Blue,
+    White,
+    Amber,
+    Uv,
+    /// Subtractive color mixing, like the flags of a CMY moving head.
+    Cyan,
+    Magenta,
+    Yellow,
+    /// Color temperature correction towards warm (orange) or cold (blue).
+    Cto,
+    Ctb,
+    Strobe,
+    Pan,
+    Tilt,
+    Gobo,
+    Macro,
+    /// A channel Blaulicht has no role for, it keeps its default value.
+    Generic,
+}
+
+/// A value range of a channel with a meaning of its own, e.g. one gobo or a strobe speed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelRange {
+    pub from: u8,
+    pub to: u8,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelDefinition {
+    /// Unique within the profile, modes refer to channels by name.
+    pub name: String,
+    pub attribute: Attribute,
+    /// Channels carrying the less significant bytes of this attribute, e.g. "Pan fine".
+    #[serde(default)]
+    pub fine_channels: Vec<String>,
+    /// Sent whenever nothing writes the attribute.
+    #[serde(default)]
+    pub default_value: u8,
+    #[serde(default)]
+    pub ranges: Vec<ChannelRange>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Mode {
+    pub name: String,
+    /// Channel names in DMX order, starting at the fixture's start address.
+    pub channels: Vec<String>,
+}
+
+impl Mode {
+    /// Number of DMX channels the fixture occupies in this mode.
+    pub fn footprint(&self) -> u16 {
+        self.channels.len() as u16
+    }
+}
+
+/// Channel layout of one fixture type.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FixtureProfile {
+    pub manufacturer: String,
+    pub name: String,
+    pub channels: Vec<ChannelDefinition>,
+    pub modes: Vec<Mode>,
+}
+
+/// Where an attribute lives within the footprint of a mode.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeChannels {
+    pub attribute: Attribute,
+    /// Offsets from the start address, the coarse channel first.
+    pub offsets: Vec<u16>,
+    pub default_value: u8,
+}
+
+impl FixtureProfile {
+    /// Identifies the profile in the library and in the patch.
+    pub fn id(&self) -> String {
+        format!("{}/{}", self.manufacturer, self.name)
+    }
+
+    pub fn channel(&self, name: &str) -> Option<&ChannelDefinition> {
+        self.channels.iter().find(|c| c.name == name)
+    }
+
+    pub fn mode(&self, name: &str) -> Option<&Mode> {
+        self.modes.iter().find(|m| m.name == name)
+    }
+
+    /// Checks that every channel a mode or fine channel refers to exists.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.modes.is_empty() {
+            return Err(format!("{}: no modes", self.id()));
+        }
+
+        for channel in &self.channels {
+            if let Some(fine) = channel
+                .fine_channels
+                .iter()
+                .find(|f| self.channel(f).is_none())
+            {
+                return Err(format!(
+                    "{}: fine channel {fine} of {} does not exist",
+                    self.id(),
+                    channel.name
+                ));
+            }
+        }
+
+        for mode in &self.modes {
+            if let Some(name) = mode.channels.iter().find(|c| self.channel(c).is_none()) {
+                return Err(format!(
+                    "{}: channel {name} of mode {} does not exist",
+                    self.id(),
+                    mode.name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the attributes of a mode to channel offsets.
+    ///
+    /// Fine channels are attached to their coarse channel. A mode may leave them out,
+    /// in which case the attribute is only 8 bit.
+    pub fn attributes(&self, mode: &Mode) -> Vec<AttributeChannels> {
+        let offset_of = |name: &str| mode.channels.iter().position(|c| c == name);
+        let is_fine = |name: &str| {
+            self.channels
+                .iter()
+                .any(|c| c.fine_channels.iter().any(|f| f == name))
+        };
+
+        mode.channels
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| !is_fine(name))
+            .filter_map(|(offset, name)| {
+                let channel = self.channel(name)?;
+                let mut offsets = vec![offset as u16];
+                offsets.extend(
+                    channel
+                        .fine_channels
+                        .iter()
+                        .map_while(|fine| offset_of(fine))
+                        .map(|offset| offset as u16),
+                );
+
+                Some(AttributeChannels {
+                    attribute: channel.attribute,
+                    offsets,
+                    default_value: channel.default_value,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Splits a value from 0.0 to 1.0 over `out.len()` channels, the most significant byte first.
+pub fn encode(value: f32, out: &mut [u8]) {
+    let bytes = out.len().min(4) as u32;
+    if bytes == 0 {
+        return;
+    }
+
+    let max = (1u64 << (8 * bytes)) - 1;
+    let scaled = (value.clamp(0.0, 1.0) as f64 * max as f64).round() as u64;
+
+    for (i, byte) in out.iter_mut().take(bytes as usize).enumerate() {
+        let shift = 8 * (bytes as usize - 1 - i);
+        *byte = (scaled >> shift) as u8;
+    }
+}
@@ -1,9 +1,15 @@
 pub mod audio;
+pub mod dmx;
+pub mod engine;
+pub mod fixture;
 mod inputs;
+pub mod show;
 pub mod utils;
+pub mod worker;
 
 // use serialport::{SerialPort, SerialPortType};
 use std::{
+    path::PathBuf,
     sync::{
         mpsc::{self, Receiver, Sender, SyncSender, TryRecvError}, Arc, Mutex
     }, thread::{self, JoinHandle}, time::Duration
@@ -23,16 +29,99 @@ use cpal::{
     traits::{DeviceTrait, StreamTrait},
     Device, HostId,
 };
+use dmx::{
+    interfaces::{self, DmxTiming, InterfaceDefinition},
+    master::{MasterState, PanicMode},
+    merge::MergeRange,
+    monitor::UniverseUpdate,
+    network::{NetworkInput, NetworkInputs},
+    output::{FrameBuffer, OutputCommand, OutputStatus},
+    walk::{WalkConfig, WalkState},
+};
+use engine::{
+    effect::EffectConfig,
+    layer::{self, Layer},
+    modulation::{self, Route},
+    EffectState, EngineCommand,
+};
+use fixture::{
+    group::{self, FixtureGroup, Selection},
+    import::ImportReport,
+    mvr::Rig,
+    patch::{self, PatchIssue, PatchedFixture},
+    profile::FixtureProfile,
+};
 use serde::Serialize;
-use serialport::{SerialPort, SerialPortType};
+use serialport::SerialPortType;
+use show::Show;
 use tauri::{AppHandle, Builder, Emitter, Manager, State, Window};
 use utils::init_logger;
 
 // use crate::inputs::start;
 
+/// How long commands wait for the DMX output thread to answer.
+const DMX_REPLY_TIMEOUT: Duration = Duration::from_millis(500);
+
 struct AppData {
     welcome_message: &'static str,
     from_frontend: Mutex<Sender<FromFrontend>>,
+    dmx_output: Mutex<Sender<OutputCommand>>,
+    engine: Mutex<Sender<EngineCommand>>,
+    interfaces_path: PathBuf,
+    fixtures_path: PathBuf,
+    /// The profiles in `fixtures_path` and the builtin ones, re-read when profiles are added.
+    library: Mutex<Vec<FixtureProfile>>,
+    show: Mutex<Show>,
+    show_path: PathBuf,
+}
+
+impl AppData {
+    fn send_output(&self, command: OutputCommand) -> Result<(), String> {
+        let sender = self.dmx_output.lock().unwrap();
+        sender.send(command).map_err(|err| err.to_string())
+    }
+
+    fn send_engine(&self, command: EngineCommand) -> Result<(), String> {
+        let sender = self.engine.lock().unwrap();
+        sender.send(command).map_err(|err| err.to_string())
+    }
+
+    /// Re-reads the fixture library and hands the rig to the engine again, the profiles
+    /// of patched fixtures may have changed.
+    fn reload_library(&self) -> Result<(), String> {
+        *self.library.lock().unwrap() = fixture::load_library(&self.fixtures_path);
+        let show = self.show.lock().unwrap();
+        self.send_rig(&show)
+    }
+
+    /// Hands the patched fixtures and the groups to the effect engine.
+    fn send_rig(&self, show: &Show) -> Result<(), String> {
+        let profiles = self.library.lock().unwrap();
+        self.send_engine(EngineCommand::SetRig {
+            fixtures: patch::resolve(&show.patch, &profiles),
+            groups: show.groups.clone(),
+        })
+    }
+
+    /// Points the grand master and blackout at the dimmers of the patch.
+    fn send_intensity_channels(
+        &self,
+        previous: &[PatchedFixture],
+        patch: &[PatchedFixture],
+    ) -> Result<(), String> {
+        let profiles = self.library.lock().unwrap();
+        let mut channels = patch::intensity_channels(&patch::resolve(patch, &profiles));
+
+        // Universes without fixtures anymore go back to a full blackout.
+        for fixture in previous {
+            channels.entry(fixture.universe).or_default();
+        }
+
+        for (universe, channels) in channels {
+            self.send_output(OutputCommand::SetIntensityChannels { universe, channels })?;
+        }
+        Ok(())
+    }
 }
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
@@ -95,6 +184,477 @@ fn select_device(state: State<'_, AppData>, host: String, device: String) -> Res
     Ok(())
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrontendSerialPort {
+    name: String,
+    vid: Option<u16>,
+    pid: Option<u16>,
+    serial_number: Option<String>,
+    manufacturer: Option<String>,
+    product: Option<String>,
+    /// Name of the registered interface definition matching this port, if any.
+    interface: Option<String>,
+}
+
+#[tauri::command]
+fn list_serial_ports(state: State<'_, AppData>) -> Result<Vec<FrontendSerialPort>, String> {
+    let definitions = interfaces::load(&state.interfaces_path);
+    let ports = serialport::available_ports().map_err(|err| err.to_string())?;
+
+    Ok(ports
+        .iter()
+        .map(|port| {
+            let interface = interfaces::definition_for(&definitions, port).map(|d| d.name.clone());
+
+            match &port.port_type {
+                SerialPortType::UsbPort(usb) => FrontendSerialPort {
+                    name: port.port_name.clone(),
+                    vid: Some(usb.vid),
+                    pid: Some(usb.pid),
+                    serial_number: usb.serial_number.clone(),
+                    manufacturer: usb.manufacturer.clone(),
+                    product: usb.product.clone(),
+                    interface,
+                },
+                _ => FrontendSerialPort {
+                    name: port.port_name.clone(),
+                    vid: None,
+                    pid: None,
+                    serial_number: None,
+                    manufacturer: None,
+                    product: None,
+                    interface,
+                },
+            }
+        })
+        .collect())
+}
+
+/// Outputs the given universe on an explicitly chosen serial port.
+#[tauri::command]
+fn select_serial_port(
+    state: State<'_, AppData>,
+    universe: u16,
+    port: String,
+) -> Result<(), String> {
+    state.send_output(OutputCommand::SelectPort { universe, port })
+}
+
+#[tauri::command]
+fn list_interfaces(state: State<'_, AppData>) -> Vec<InterfaceDefinition> {
+    interfaces::load(&state.interfaces_path)
+}
+
+#[tauri::command]
+fn save_interfaces(
+    state: State<'_, AppData>,
+    definitions: Vec<InterfaceDefinition>,
+) -> Result<(), String> {
+    interfaces::save(&state.interfaces_path, &definitions).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn list_fixture_profiles(state: State<'_, AppData>) -> Vec<FixtureProfile> {
+    state.library.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn save_fixture_profile(state: State<'_, AppData>, profile: FixtureProfile) -> Result<(), String> {
+    fixture::save_profile(&state.fixtures_path, &profile)?;
+    state.reload_library()
+}
+
+/// Converts a fixture definition of another tool and adds it to the library.
+#[tauri::command]
+fn import_fixture_profile(
+    state: State<'_, AppData>,
+    path: PathBuf,
+) -> Result<ImportReport, String> {
+    let report = fixture::import::import(&path)?;
+    fixture::save_profile(&state.fixtures_path, &report.profile)?;
+
+    // Kept for MVR exports, which pass GDTF files on unchanged.
+    if path.extension().is_some_and(|e| e == "gdtf") {
+        std::fs::copy(&path, fixture::gdtf_path(&state.fixtures_path, &report.profile))
+            .map_err(|err| err.to_string())?;
+    }
+
+    for unmapped in &report.unmapped {
+        println!("[fixture] {}: not imported: {unmapped}", report.profile.id());
+    }
+    state.reload_library()?;
+    Ok(report)
+}
+
+#[tauri::command]
+fn get_patch(state: State<'_, AppData>) -> Vec<PatchedFixture> {
+    state.show.lock().unwrap().patch.clone()
+}
+
+/// Replaces the patch and saves the show. Problems are returned, but do not prevent saving.
+#[tauri::command]
+fn set_patch(
+    state: State<'_, AppData>,
+    patch: Vec<PatchedFixture>,
+) -> Result<Vec<PatchIssue>, String> {
+    let issues = patch::validate(&patch, &state.library.lock().unwrap());
+
+    let mut show = state.show.lock().unwrap();
+    let previous = std::mem::replace(&mut show.patch, patch);
+    show::save(&state.show_path, &show).map_err(|err| err.to_string())?;
+    state.send_intensity_channels(&previous, &show.patch)?;
+    state.send_rig(&show)?;
+
+    Ok(issues)
+}
+
+#[tauri::command]
+fn validate_patch(state: State<'_, AppData>, patch: Vec<PatchedFixture>) -> Vec<PatchIssue> {
+    patch::validate(&patch, &state.library.lock().unwrap())
+}
+
+#[tauri::command]
+fn get_groups(state: State<'_, AppData>) -> Vec<FixtureGroup> {
+    state.show.lock().unwrap().groups.clone()
+}
+
+/// Replaces all fixture groups and saves the show.
+#[tauri::command]
+fn set_groups(state: State<'_, AppData>, groups: Vec<FixtureGroup>) -> Result<(), String> {
+    group::validate(&groups)?;
+
+    let mut show = state.show.lock().unwrap();
+    show.groups = groups;
+    show::save(&state.show_path, &show).map_err(|err| err.to_string())?;
+    state.send_rig(&show)
+}
+
+#[tauri::command]
+fn select_fixtures(fixtures: Vec<u32>, selection: Selection) -> Vec<u32> {
+    selection.apply(&fixtures)
+}
+
+#[tauri::command]
+fn list_effects(state: State<'_, AppData>) -> Result<Vec<EffectState>, String> {
+    let (reply, response) = mpsc::channel();
+    state.send_engine(EngineCommand::ListEffects(reply))?;
+
+    response
+        .recv_timeout(DMX_REPLY_TIMEOUT)
+        .map_err(|_| "Effect engine is not running".to_string())
+}
+
+#[tauri::command]
+fn add_effect(
+    state: State<'_, AppData>,
+    name: String,
+    config: EffectConfig,
+    group: String,
+    layer: String,
+) -> Result<(), String> {
+    let (reply, response) = mpsc::channel();
+    state.send_engine(EngineCommand::AddEffect {
+        name,
+        config,
+        group,
+        layer,
+        reply,
+    })?;
+
+    response
+        .recv_timeout(DMX_REPLY_TIMEOUT)
+        .map_err(|_| "Effect engine is not running".to_string())?
+}
+
+#[tauri::command]
+fn remove_effect(state: State<'_, AppData>, name: String) -> Result<(), String> {
+    state.send_engine(EngineCommand::RemoveEffect(name))
+}
+
+#[tauri::command]
+fn set_effect_group(state: State<'_, AppData>, name: String, group: String) -> Result<(), String> {
+    state.send_engine(EngineCommand::SetEffectGroup { name, group })
+}
+
+#[tauri::command]
+fn set_effect_layer(state: State<'_, AppData>, name: String, layer: String) -> Result<(), String> {
+    state.send_engine(EngineCommand::SetEffectLayer { name, layer })
+}
+
+#[tauri::command]
+fn set_effect_param(
+    state: State<'_, AppData>,
+    name: String,
+    param: String,
+    value: f32,
+) -> Result<(), String> {
+    state.send_engine(EngineCommand::SetEffectParam { name, param, value })
+}
+
+#[tauri::command]
+fn start_effect(state: State<'_, AppData>, name: String) -> Result<(), String> {
+    state.send_engine(EngineCommand::StartEffect(name))
+}
+
+#[tauri::command]
+fn stop_effect(state: State<'_, AppData>, name: String) -> Result<(), String> {
+    state.send_engine(EngineCommand::StopEffect(name))
+}
+
+#[tauri::command]
+fn list_layers(state: State<'_, AppData>) -> Result<Vec<Layer>, String> {
+    let (reply, response) = mpsc::channel();
+    state.send_engine(EngineCommand::ListLayers(reply))?;
+
+    response
+        .recv_timeout(DMX_REPLY_TIMEOUT)
+        .map_err(|_| "Effect engine is not running".to_string())
+}
+
+/// Replaces the layer stack, bottom layer first.
+#[tauri::command]
+fn set_layers(state: State<'_, AppData>, layers: Vec<Layer>) -> Result<(), String> {
+    layer::validate(&layers)?;
+    state.send_engine(EngineCommand::SetLayers(layers))
+}
+
+#[tauri::command]
+fn get_routes(state: State<'_, AppData>) -> Vec<Route> {
+    state.show.lock().unwrap().routes.clone()
+}
+
+/// Replaces the routes of the modulation matrix and saves the show.
+#[tauri::command]
+fn set_routes(state: State<'_, AppData>, routes: Vec<Route>) -> Result<(), String> {
+    modulation::validate(&routes)?;
+
+    let mut show = state.show.lock().unwrap();
+    show.routes = routes;
+    show::save(&state.show_path, &show).map_err(|err| err.to_string())?;
+    state.send_engine(EngineCommand::SetRoutes(show.routes.clone()))
+}
+
+/// Replaces the patch with the fixtures of an MVR file, adding its GDTF files to the library.
+#[tauri::command]
+fn import_mvr(state: State<'_, AppData>, path: PathBuf) -> Result<Rig, String> {
+    let rig = fixture::mvr::import(&path)?;
+
+    for gdtf in &rig.gdtf_files {
+        let profile = &gdtf.report.profile;
+        fixture::save_profile(&state.fixtures_path, profile)?;
+        std::fs::write(fixture::gdtf_path(&state.fixtures_path, profile), &gdtf.raw)
+            .map_err(|err| err.to_string())?;
+
+        for unmapped in &gdtf.report.unmapped {
+            println!("[fixture] {}: not imported: {unmapped}", profile.id());
+        }
+    }
+    for warning in &rig.warnings {
+        println!("[fixture] {}: {warning}", path.display());
+    }
+    state.reload_library()?;
+
+    let issues = set_patch(state, rig.patch.clone())?;
+    for issue in issues {
+        println!("[show] Imported patch: {issue:?}");
+    }
+    Ok(rig)
+}
+
+#[tauri::command]
+fn export_mvr(state: State<'_, AppData>, path: PathBuf) -> Result<(), String> {
+    let show = state.show.lock().unwrap();
+    fixture::mvr::export(
+        &path,
+        &show.patch,
+        &state.library.lock().unwrap(),
+        &state.fixtures_path,
+    )
+}
+
+#[tauri::command]
+fn set_dmx_refresh_rate(state: State<'_, AppData>, hz: f32) -> Result<(), String> {
+    if !hz.is_finite() {
+        return Err(format!("Invalid refresh rate: {hz}"));
+    }
+    state.send_output(OutputCommand::SetRefreshRate(hz))
+}
+
+#[tauri::command]
+fn set_dmx_timing(
+    state: State<'_, AppData>,
+    universe: u16,
+    timing: DmxTiming,
+) -> Result<(), String> {
+    state.send_output(OutputCommand::SetTiming { universe, timing })
+}
+
+#[tauri::command]
+fn start_recording(state: State<'_, AppData>, path: PathBuf) -> Result<(), String> {
+    state.send_output(OutputCommand::StartRecording(path))
+}
+
+#[tauri::command]
+fn stop_recording(state: State<'_, AppData>) -> Result<(), String> {
+    state.send_output(OutputCommand::StopRecording)
+}
+
+#[tauri::command]
+fn start_playback(state: State<'_, AppData>, path: PathBuf, looped: bool) -> Result<(), String> {
+    state.send_output(OutputCommand::StartPlayback { path, looped })
+}
+
+#[tauri::command]
+fn stop_playback(state: State<'_, AppData>) -> Result<(), String> {
+    state.send_output(OutputCommand::StopPlayback)
+}
+
+#[tauri::command]
+fn set_network_inputs(state: State<'_, AppData>, inputs: Vec<NetworkInput>) -> Result<(), String> {
+    state.send_output(OutputCommand::SetNetworkInputs(inputs))
+}
+
+#[tauri::command]
+fn resync_monitor(state: State<'_, AppData>) -> Result<(), String> {
+    state.send_output(OutputCommand::ResyncMonitor)
+}
+
+#[tauri::command]
+fn set_channel(
+    state: State<'_, AppData>,
+    universe: u16,
+    channel: u16,
+    value: u8,
+) -> Result<(), String> {
+    state.send_output(OutputCommand::SetChannels {
+        universe,
+        start: channel,
+        values: vec![value],
+    })
+}
+
+#[tauri::command]
+fn set_channels(
+    state: State<'_, AppData>,
+    universe: u16,
+    start: u16,
+    values: Vec<u8>,
+) -> Result<(), String> {
+    state.send_output(OutputCommand::SetChannels {
+        universe,
+        start,
+        values,
+    })
+}
+
+#[tauri::command]
+fn get_channels(
+    state: State<'_, AppData>,
+    universe: u16,
+    start: u16,
+    count: u16,
+) -> Result<Vec<u8>, String> {
+    let (reply, response) = mpsc::channel();
+    state.send_output(OutputCommand::GetChannels {
+        universe,
+        start,
+        count,
+        reply,
+    })?;
+
+    response
+        .recv_timeout(DMX_REPLY_TIMEOUT)
+        .map_err(|_| "DMX output is not running".to_string())
+}
+
+#[tauri::command]
+fn get_channel(state: State<'_, AppData>, universe: u16, channel: u16) -> Result<u8, String> {
+    let values = get_channels(state, universe, channel, 1)?;
+    values
+        .first()
+        .copied()
+        .ok_or_else(|| format!("No such channel: {channel}"))
+}
+
+#[tauri::command]
+fn release_channels(
+    state: State<'_, AppData>,
+    universe: u16,
+    start: u16,
+    count: u16,
+) -> Result<(), String> {
+    state.send_output(OutputCommand::ReleaseChannels {
+        universe,
+        start,
+        count,
+    })
+}
+
+#[tauri::command]
+fn release_all_channels(state: State<'_, AppData>) -> Result<(), String> {
+    state.send_output(OutputCommand::ReleaseAllChannels)
+}
+
+#[tauri::command]
+fn set_grand_master(state: State<'_, AppData>, level: f32) -> Result<(), String> {
+    state.send_output(OutputCommand::SetGrandMaster(level))
+}
+
+#[tauri::command]
+fn set_blackout(state: State<'_, AppData>, blackout: bool) -> Result<(), String> {
+    state.send_output(OutputCommand::SetBlackout(blackout))
+}
+
+#[tauri::command]
+fn panic(state: State<'_, AppData>, mode: PanicMode) -> Result<(), String> {
+    state.send_output(OutputCommand::Panic(mode))
+}
+
+#[tauri::command]
+fn release_panic(state: State<'_, AppData>) -> Result<(), String> {
+    state.send_output(OutputCommand::ReleasePanic)
+}
+
+#[tauri::command]
+fn set_intensity_channels(
+    state: State<'_, AppData>,
+    universe: u16,
+    channels: Vec<u16>,
+) -> Result<(), String> {
+    state.send_output(OutputCommand::SetIntensityChannels { universe, channels })
+}
+
+#[tauri::command]
+fn get_masters(state: State<'_, AppData>) -> Result<MasterState, String> {
+    let (reply, response) = mpsc::channel();
+    state.send_output(OutputCommand::GetMasters(reply))?;
+
+    response
+        .recv_timeout(DMX_REPLY_TIMEOUT)
+        .map_err(|_| "DMX output is not running".to_string())
+}
+
+#[tauri::command]
+fn start_channel_walk(state: State<'_, AppData>, config: WalkConfig) -> Result<(), String> {
+    state.send_output(OutputCommand::StartWalk(config))
+}
+
+#[tauri::command]
+fn stop_channel_walk(state: State<'_, AppData>) -> Result<(), String> {
+    state.send_output(OutputCommand::StopWalk)
+}
+
+#[tauri::command]
+fn step_channel_walk(state: State<'_, AppData>, forward: bool) -> Result<(), String> {
+    state.send_output(OutputCommand::StepWalk { forward })
+}
+
+#[tauri::command]
+fn set_merge_ranges(state: State<'_, AppData>, ranges: Vec<MergeRange>) -> Result<(), String> {
+    state.send_output(OutputCommand::SetMergeRanges(ranges))
+}
+
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Heartbeat {
@@ -113,11 +673,19 @@ fn socket(state: State<'_, AppData>, window: Window) {
     };
 }
 
-#[derive(Serialize, Clone, Copy)]
+#[derive(Serialize, Clone)]
 enum ToFrontend {
     Volume(u8),
     Beat(u8),
     Speed(usize),
+    /// Frames per second actually achieved by the DMX output thread.
+    DmxFrameRate(f32),
+    DmxStatus { universe: u16, status: OutputStatus },
+    /// Throttled diff of the output frame of one universe.
+    Universe(UniverseUpdate),
+    /// Grand master, blackout and panic, sent whenever one of them changes.
+    Masters(MasterState),
+    Walk(Option<WalkState>),
     Heartbeat,
 }
 
@@ -127,72 +695,14 @@ enum FromFrontend {
     SelectInputDevice(Device),
 }
 
-struct DmxUniverse {
-    serial: Box<dyn SerialPort>,
-    channels: [u8; 513],
-}
-
-impl DmxUniverse {
-    fn new(port_path: String) -> Self {
-        let port = serialport::new(port_path, 250000)
-            .timeout(Duration::from_millis(1))
-            .stop_bits(serialport::StopBits::Two)
-            .data_bits(serialport::DataBits::Eight)
-            .parity(serialport::Parity::None)
-            .open()
-            .expect("Failed to open port");
-
-        Self {
-            serial: port,
-            channels: [0; 513],
-        }
-    }
-
-    fn signal(&mut self, signal: Signal) {
-        match signal {
-            Signal::Beat(volume) => {
-                // TODO: engine here
-                if volume > 1 {
-                    self.channels[1] = 255;
-                    self.channels[2] = 255;
-                    self.channels[3] = 255;
-                    self.channels[4] = 255;
-                } else {
-                    self.channels[1] = 0;
-                }
-            }
-            Signal::Bass(_) => todo!(),
-            Signal::Volume(_) => todo!(),
-        }
-    }
-
-    fn send_break(&self, duration: Duration) {
-        self.serial.set_break().expect("Failed to set break");
-        spin_sleep::sleep(duration);
-        self.serial.clear_break().expect("Failed to clear break");
-    }
-
-    fn write_to_serial(&mut self) {
-        self.send_break(Duration::from_micros(100));
-        spin_sleep::sleep(Duration::from_micros(100));
-        self.serial.write_all(&self.channels).unwrap();
-        self.serial.flush().unwrap();
-    }
-}
-
-struct UsbDevice {
-    vid: u16,
-    pid: u16,
-}
-
-const EUROLITE_USB_DMX512_PRO_CABLE_INTERFACE: UsbDevice = UsbDevice {
-    vid: 1027,
-    pid: 24577,
-};
-
-const USB_DEVICES: [UsbDevice; 1] = [EUROLITE_USB_DMX512_PRO_CABLE_INTERFACE];
-
-async fn audio_thread(from_frontend: Receiver<FromFrontend>) {
+/// Waits for the frontend, then forwards audio and system messages to it and runs the
+/// audio detector. DMX output and the effect engine do not depend on it.
+async fn audio_thread(
+    from_frontend: Receiver<FromFrontend>,
+    system_out: Sender<SystemMessage>,
+    system_receiver: Receiver<SystemMessage>,
+    analysis_out: Sender<Signal>,
+) {
     let begin_msg = from_frontend.recv().unwrap();
     println!("[audio] Frontend connected!");
 
@@ -211,31 +721,24 @@ async fn audio_thread(from_frontend: Receiver<FromFrontend>) {
 // <<<<<<< Updated upstream
     // From audio to frontend.
     let (signal_out, signal_receiver) = mpsc::channel();
-    let (system_out, system_receiver) = mpsc::channel();
 
     let w = window.clone();
 
     thread::spawn(move || {
-        let ports = serialport::available_ports().unwrap();
-        let port = ports.iter().find(|p| {
-            let SerialPortType::UsbPort(usb) = p.port_type.clone() else {
-                return false;
-            };
-
-            USB_DEVICES
-                .iter()
-                .any(|d| d.pid == usb.pid && d.vid == usb.pid)
-        });
-        let name = port.unwrap().port_name.clone();
-        println!("Found port: {name}");
-        DmxUniverse::new(name);
-
         loop {
-            // Dispatch signals to frontend and to DMX engine.
+            // Dispatch signals to frontend and to the effect engine.
             match signal_receiver.try_recv() {
-                Ok(Signal::Beat(v)) => w.emit("msg", ToFrontend::Beat(v)).unwrap(),
-                Ok(Signal::Bass(_)) => todo!(),
-                Ok(Signal::Volume(v)) => w.emit("msg", ToFrontend::Volume(v)).unwrap(),
+                Ok(Signal::Beat(v)) => {
+                    let _ = analysis_out.send(Signal::Beat(v));
+                    w.emit("msg", ToFrontend::Beat(v)).unwrap()
+                }
+                Ok(Signal::Bass(v)) => {
+                    let _ = analysis_out.send(Signal::Bass(v));
+                }
+                Ok(Signal::Volume(v)) => {
+                    let _ = analysis_out.send(Signal::Volume(v));
+                    w.emit("msg", ToFrontend::Volume(v)).unwrap()
+                }
                 Err(TryRecvError::Empty) => {}
                 Err(err) => panic!("{err:?}"),
             }
@@ -244,6 +747,19 @@ async fn audio_thread(from_frontend: Receiver<FromFrontend>) {
                 Ok(SystemMessage::LoopSpeed(speed)) => w
                     .emit("msg", ToFrontend::Speed(speed.as_micros() as usize))
                     .unwrap(),
+                Ok(SystemMessage::DmxFrameRate(rate)) => {
+                    w.emit("msg", ToFrontend::DmxFrameRate(rate)).unwrap()
+                }
+                Ok(SystemMessage::DmxOutputStatus { universe, status }) => w
+                    .emit("msg", ToFrontend::DmxStatus { universe, status })
+                    .unwrap(),
+                Ok(SystemMessage::Universe(update)) => {
+                    w.emit("msg", ToFrontend::Universe(update)).unwrap()
+                }
+                Ok(SystemMessage::Masters(masters)) => {
+                    w.emit("msg", ToFrontend::Masters(masters)).unwrap()
+                }
+                Ok(SystemMessage::Walk(walk)) => w.emit("msg", ToFrontend::Walk(walk)).unwrap(),
                 Err(TryRecvError::Empty) => {}
                 Err(err) => panic!("{err:?}"),
             }
@@ -319,6 +835,8 @@ async fn audio_thread(from_frontend: Receiver<FromFrontend>) {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let (from_frontend_sender, from_frontend_receiver) = mpsc::channel();
+    let (dmx_output_sender, dmx_output_receiver) = mpsc::channel();
+    let (engine_sender, engine_receiver) = mpsc::channel();
 
     // thread::spawn(|| {
     //     audio::foo();
@@ -361,22 +879,124 @@ pub fn run() {
     //
     // return;
 
-    // thread::spawn(async { audio_thread(from_frontend_receiver).await });
-
     Builder::default()
         .plugin(tauri_plugin_shell::init())
         // .plugin(tauri_plugin_websocket::init())
         .setup(|app| {
+            let config_dir = app.path().app_config_dir()?;
+            let interfaces_path = config_dir.join(interfaces::INTERFACES_FILE);
+            let fixtures_path = config_dir.join(fixture::LIBRARY_DIR);
+            let show_path = config_dir.join(show::SHOW_FILE);
+            let show = show::load(&show_path)?;
+
+            // Output, network input and the engine run from the start, only what goes to
+            // the frontend waits for a window to connect.
+            let (system_out, system_receiver) = mpsc::channel();
+            let network = Arc::new(NetworkInputs::default());
+            dmx::network::spawn(network.clone());
+
+            let frames = Arc::new(FrameBuffer::new(1));
+            dmx::output::spawn(
+                interfaces_path.clone(),
+                frames.clone(),
+                network,
+                dmx_output_receiver,
+                system_out.clone(),
+            );
+
+            // From audio to the effect engine.
+            let (analysis_out, analysis_receiver) = mpsc::channel();
+            engine::spawn(frames, engine_receiver, analysis_receiver);
+
+            thread::spawn(move || {
+                tauri::async_runtime::block_on(audio_thread(
+                    from_frontend_receiver,
+                    system_out,
+                    system_receiver,
+                    analysis_out,
+                ))
+            });
+
             app.manage(AppData {
                 welcome_message: "Welcome to Tauri!",
                 from_frontend: Mutex::new(from_frontend_sender),
+                dmx_output: Mutex::new(dmx_output_sender),
+                engine: Mutex::new(engine_sender),
+                interfaces_path,
+                library: Mutex::new(fixture::load_library(&fixtures_path)),
+                fixtures_path,
+                show: Mutex::new(show),
+                show_path,
             });
+
+            let state = app.state::<AppData>();
+            let show = state.show.lock().unwrap().clone();
+            if let Err(err) = state.send_intensity_channels(&[], &show.patch) {
+                eprintln!("[show] Failed to apply the patch: {err}");
+            }
+            if let Err(err) = state.send_rig(&show) {
+                eprintln!("[show] Failed to hand the rig to the engine: {err}");
+            }
+            if let Err(err) = state.send_engine(EngineCommand::SetRoutes(show.routes)) {
+                eprintln!("[show] Failed to hand the modulation routes to the engine: {err}");
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             socket,
             list_devices,
-            select_device
+            select_device,
+            list_serial_ports,
+            select_serial_port,
+            list_interfaces,
+            save_interfaces,
+            list_fixture_profiles,
+            save_fixture_profile,
+            import_fixture_profile,
+            get_patch,
+            set_patch,
+            validate_patch,
+            import_mvr,
+            export_mvr,
+            get_groups,
+            set_groups,
+            select_fixtures,
+            list_effects,
+            add_effect,
+            remove_effect,
+            set_effect_group,
+            set_effect_layer,
+            set_effect_param,
+            start_effect,
+            stop_effect,
+            list_layers,
+            set_layers,
+            get_routes,
+            set_routes,
+            set_dmx_refresh_rate,
+            set_dmx_timing,
+            start_recording,
+            stop_recording,
+            start_playback,
+            stop_playback,
+            set_network_inputs,
+            set_merge_ranges,
+            resync_monitor,
+            set_channel,
+            set_channels,
+            get_channel,
+            get_channels,
+            release_channels,
+            release_all_channels,
+            set_grand_master,
+            set_blackout,
+            panic,
+            release_panic,
+            set_intensity_channels,
+            get_masters,
+            start_channel_walk,
+            stop_channel_walk,
+            step_channel_walk
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,66 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::modulation::Route,
+    fixture::{group::FixtureGroup, patch::PatchedFixture},
+};
+
+/// Name of the file (inside the app config directory) holding the current show.
+pub const SHOW_FILE: &str = "show.json";
+
+/// Everything describing a rig and what runs on it, saved as a single JSON file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Show {
+    pub patch: Vec<PatchedFixture>,
+    pub groups: Vec<FixtureGroup>,
+    /// The modulation matrix, from the analysis to effect parameters.
+    pub routes: Vec<Route>,
+}
+
+/// Where [`load`] moves a show file it cannot parse, e.g. `show.json.bak`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Loads the show, or an empty one if there is none yet.
+///
+/// A show file that cannot be parsed is moved to its [`backup_path`] first, so that
+/// saving the empty show does not overwrite it. Fails if the file cannot be read or moved.
+pub fn load(path: &Path) -> io::Result<Show> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Show::default()),
+        Err(err) => return Err(err),
+    };
+
+    match serde_json::from_str(&raw) {
+        Ok(show) => Ok(show),
+        Err(err) => {
+            let backup = backup_path(path);
+            fs::rename(path, &backup)?;
+            eprintln!(
+                "[show] Invalid show file {}, moved it to {}: {err}",
+                path.display(),
+                backup.display()
+            );
+            Ok(Show::default())
+        }
+    }
+}
+
+pub fn save(path: &Path, show: &Show) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let raw = serde_json::to_string_pretty(show)?;
+    fs::write(path, raw)
+}
@@ -0,0 +1,39 @@
+//! Helpers shared by the threads running next to the frontend, like the DMX output
+//! and the effect engine.
+
+use std::{
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
+
+/// Answers a request sent along with a reply channel. The requester may have given up
+/// waiting already, in which case the answer is dropped.
+pub fn reply<T>(reply: Sender<T>, value: T) {
+    let _ = reply.send(value);
+}
+
+/// Paces a loop to a fixed period.
+pub struct Ticker {
+    next: Instant,
+}
+
+impl Default for Ticker {
+    fn default() -> Self {
+        Self {
+            next: Instant::now(),
+        }
+    }
+}
+
+impl Ticker {
+    /// Sleeps until one period after the previous tick. Missed deadlines are dropped
+    /// instead of bursting ticks to catch up.
+    pub fn wait(&mut self, period: Duration) {
+        let now = Instant::now();
+        self.next += period;
+        if self.next < now {
+            self.next = now;
+        }
+        spin_sleep::sleep(self.next - now);
+    }
+}
@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use blaulicht_lib::{audio::Signal, engine::analysis::Analyzer};
+
+/// Feeds one beat: the level rises above the threshold and falls back.
+fn beat(analyzer: &mut Analyzer, at: Instant) {
+    analyzer.signal(Signal::Beat(255), at);
+    analyzer.signal(Signal::Beat(0), at + Duration::from_millis(50));
+}
+
+#[test]
+fn counts_each_beat_once() {
+    let mut analyzer = Analyzer::default();
+    let start = Instant::now();
+
+    analyzer.signal(Signal::Beat(0), start);
+    analyzer.signal(Signal::Beat(200), start);
+    // Still above the threshold, no new beat.
+    analyzer.signal(Signal::Beat(255), start);
+
+    let analysis = analyzer.frame(start);
+    assert!(analysis.beat);
+    assert_eq!(analysis.beat_index, 1);
+
+    // The beat flag only lasts for one frame.
+    assert!(!analyzer.frame(start).beat);
+}
+
+#[test]
+fn tracks_tempo_and_phase() {
+    let mut analyzer = Analyzer::default();
+    let start = Instant::now();
+    let interval = Duration::from_millis(500);
+
+    analyzer.signal(Signal::Beat(0), start);
+    for i in 0..8 {
+        beat(&mut analyzer, start + interval * i);
+    }
+
+    let last_beat = start + interval * 7;
+    let analysis = analyzer.frame(last_beat + interval / 4);
+    assert!((analysis.tempo - 120.0).abs() < 0.5, "{}", analysis.tempo);
+    assert!((analysis.phase - 0.25).abs() < 0.01, "{}", analysis.phase);
+    assert_eq!(analysis.beat_index, 8);
+}
+
+#[test]
+fn detects_silence_and_drops() {
+    let mut analyzer = Analyzer::default();
+    let start = Instant::now();
+    assert!(analyzer.frame(start).silence);
+
+    analyzer.signal(Signal::Volume(128), start);
+    analyzer.signal(Signal::Bass(255), start);
+    let analysis = analyzer.frame(start);
+    assert!(!analysis.silence);
+    // The music starting is no drop.
+    assert!(!analysis.drop);
+
+    // A breakdown: still loud, but without bass.
+    let breakdown = start + Duration::from_secs(5);
+    analyzer.signal(Signal::Volume(128), breakdown);
+    analyzer.signal(Signal::Bass(0), breakdown);
+    assert!(!analyzer.frame(breakdown).drop);
+
+    analyzer.signal(Signal::Bass(255), breakdown);
+    let analysis = analyzer.frame(breakdown);
+    assert!(analysis.drop);
+    assert!(!analysis.silence);
+
+    // The drop lasts until the next breakdown, and silence follows the volume.
+    let later = breakdown + Duration::from_secs(2);
+    let analysis = analyzer.frame(later);
+    assert!(analysis.drop);
+    assert!(analysis.silence);
+    assert!(!analyzer.frame(later + Duration::from_secs(4)).drop);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use std::time::Duration;
+
+use blaulicht_lib::{
+    engine::{
+        analysis::Analysis,
+        effect::{
+            chase::{Chase, ChaseConfig, Direction, LookValue, Step, Trigger},
+            Clock, Context, Effect, Values,
+        },
+    },
+    fixture::{patch::ResolvedFixture, profile::Attribute},
+};
+
+use common::fixture;
+
+/// A running light: every step turns one fixture on.
+fn chase(ids: &[u32], trigger: Trigger, direction: Direction) -> Chase {
+    Chase::new(ChaseConfig {
+        steps: ids
+            .iter()
+            .map(|&id| Step {
+                fixtures: vec![id],
+                look: vec![LookValue {
+                    attribute: Attribute::Dimmer,
+                    value: 1.0,
+                }],
+            })
+            .collect(),
+        trigger,
+        direction,
+    })
+}
+
+/// Renders one frame and returns the id of the fixture that is on.
+fn frame(chase: &mut Chase, fixtures: &[&ResolvedFixture], beat: bool, delta: f32) -> u32 {
+    let analysis = Analysis {
+        beat,
+        ..Analysis::default()
+    };
+    let context = Context {
+        analysis: &analysis,
+        clock: Clock {
+            time: Duration::ZERO,
+            delta: Duration::from_secs_f32(delta),
+        },
+    };
+    let mut values = Values::default();
+    chase.render(&context, fixtures, &mut values);
+
+    let on: Vec<u32> = fixtures
+        .iter()
+        .filter(|f| values.get(f.id, Attribute::Dimmer) == Some(1.0))
+        .map(|f| f.id)
+        .collect();
+    assert_eq!(on.len(), 1, "{on:?}");
+    on[0]
+}
+
+#[test]
+fn advances_on_every_nth_beat() {
+    let rig: Vec<ResolvedFixture> = (1..=3).map(fixture).collect();
+    let fixtures: Vec<&ResolvedFixture> = rig.iter().collect();
+    let mut chase = chase(&[1, 2, 3], Trigger::Beats(2), Direction::Forward);
+
+    let steps: Vec<u32> = (0..6)
+        .map(|_| frame(&mut chase, &fixtures, true, 0.0))
+        .collect();
+    assert_eq!(steps, [1, 2, 2, 3, 3, 1]);
+
+    // Without beats nothing moves.
+    assert_eq!(frame(&mut chase, &fixtures, false, 1.0), 1);
+}
+
+#[test]
+fn bounces_and_runs_backward() {
+    let rig: Vec<ResolvedFixture> = (1..=3).map(fixture).collect();
+    let fixtures: Vec<&ResolvedFixture> = rig.iter().collect();
+
+    let mut bounce = chase(&[1, 2, 3], Trigger::Seconds(1.0), Direction::Bounce);
+    let steps: Vec<u32> = (0..6)
+        .map(|_| frame(&mut bounce, &fixtures, false, 1.0))
+        .collect();
+    assert_eq!(steps, [2, 3, 2, 1, 2, 3]);
+
+    let mut backward = chase(&[1, 2, 3], Trigger::Seconds(1.0), Direction::Backward);
+    let context = Context {
+        analysis: &Analysis::default(),
+        clock: Clock::default(),
+    };
+    backward.start(&context);
+    let steps: Vec<u32> = (0..4)
+        .map(|_| frame(&mut backward, &fixtures, false, 1.0))
+        .collect();
+    assert_eq!(steps, [2, 1, 3, 2]);
+}
+
+#[test]
+fn crossfades_over_part_of_the_step() {
+    let rig: Vec<ResolvedFixture> = (1..=2).map(fixture).collect();
+    let fixtures: Vec<&ResolvedFixture> = rig.iter().collect();
+    let mut chase = chase(&[1, 2], Trigger::Seconds(1.0), Direction::Forward);
+    chase.params_mut().set("crossfade", 0.5).unwrap();
+
+    let analysis = Analysis::default();
+    let mut render = |delta: f32| {
+        let context = Context {
+            analysis: &analysis,
+            clock: Clock {
+                time: Duration::ZERO,
+                delta: Duration::from_secs_f32(delta),
+            },
+        };
+        let mut values = Values::default();
+        chase.render(&context, &fixtures, &mut values);
+        [1, 2].map(|id| values.get(id, Attribute::Dimmer).unwrap())
+    };
+
+    // The second step just began, the crossfade starts from the first.
+    assert_eq!(render(1.0), [1.0, 0.0]);
+    // A quarter into the step is halfway through the crossfade.
+    let [first, second] = render(0.25);
+    assert!((first - 0.5).abs() < 0.001, "{first}");
+    assert!((second - 0.5).abs() < 0.001, "{second}");
+    assert_eq!(render(0.5), [0.0, 1.0]);
+}
@@ -0,0 +1,194 @@
+use blaulicht_lib::{
+    dmx::UNIVERSE_SIZE,
+    fixture::{
+        color::{self, Hsv, Rgb},
+        curve::{Curve, IntensityCurve},
+        movement::Movement,
+        patch::ResolvedFixture,
+        profile::{Attribute, AttributeChannels},
+    },
+};
+
+fn fixture(attributes: &[Attribute]) -> ResolvedFixture {
+    ResolvedFixture {
+        id: 1,
+        universe: 0,
+        address: 1,
+        footprint: attributes.len() as u16,
+        attributes: attributes
+            .iter()
+            .enumerate()
+            .map(|(offset, attribute)| AttributeChannels {
+                attribute: *attribute,
+                offsets: vec![offset as u16],
+                default_value: 0,
+            })
+            .collect(),
+        movement: Movement::default(),
+        intensity_curve: IntensityCurve::default(),
+    }
+}
+
+fn value(values: &[(Attribute, f32)], attribute: Attribute) -> f32 {
+    values
+        .iter()
+        .find(|(a, _)| *a == attribute)
+        .map(|(_, v)| *v)
+        .unwrap()
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 0.001,
+        "{actual} is not {expected}"
+    );
+}
+
+#[test]
+fn converts_hsv_to_rgb_and_back() {
+    let orange: Rgb = Hsv {
+        hue: 30.0,
+        saturation: 1.0,
+        value: 1.0,
+    }
+    .into();
+    assert_close(orange.red, 1.0);
+    assert_close(orange.green, 0.5);
+    assert_close(orange.blue, 0.0);
+
+    let hsv: Hsv = Rgb {
+        red: 0.2,
+        green: 0.4,
+        blue: 0.8,
+    }
+    .into();
+    assert_close(hsv.hue, 220.0);
+    assert_close(hsv.saturation, 0.75);
+    assert_close(hsv.value, 0.8);
+}
+
+#[test]
+fn moves_intensity_to_the_dimmer() {
+    let fixture = fixture(&[
+        Attribute::Dimmer,
+        Attribute::Red,
+        Attribute::Green,
+        Attribute::Blue,
+    ]);
+    let dark_red = Rgb {
+        red: 0.5,
+        green: 0.0,
+        blue: 0.0,
+    };
+    let values = color::mix(&fixture, dark_red, 0.5);
+
+    assert_close(value(&values, Attribute::Dimmer), 0.25);
+    assert_close(value(&values, Attribute::Red), 1.0);
+    assert_close(value(&values, Attribute::Green), 0.0);
+}
+
+#[test]
+fn shapes_the_intensity_of_dimmerless_fixtures_once() {
+    let mut fixture = fixture(&[Attribute::Red, Attribute::Green, Attribute::Blue]);
+    fixture.intensity_curve = IntensityCurve {
+        curve: Curve::Square,
+        min: 0.2,
+        max: 1.0,
+    };
+    let orange = Rgb {
+        red: 1.0,
+        green: 0.5,
+        blue: 0.0,
+    };
+
+    // 0.5 squared is 0.25, which the limits turn into 0.4.
+    let values = color::mix(&fixture, orange, 0.5);
+    assert_close(value(&values, Attribute::Red), 0.4);
+    assert_close(value(&values, Attribute::Green), 0.2);
+    assert_close(value(&values, Attribute::Blue), 0.0);
+
+    // The emitters go out as mixed, keeping the hue.
+    let mut frames = vec![[0; UNIVERSE_SIZE]];
+    color::write(&fixture, &mut frames, orange, 0.5);
+    assert_eq!(frames[0][..3], [102, 51, 0]);
+
+    assert!(color::mix(&fixture, orange, 0.0)
+        .iter()
+        .all(|&(_, value)| value == 0.0));
+}
+
+#[test]
+fn shapes_the_dimmer_of_fixtures_with_one() {
+    let mut fixture = fixture(&[
+        Attribute::Dimmer,
+        Attribute::Red,
+        Attribute::Green,
+        Attribute::Blue,
+    ]);
+    fixture.intensity_curve = IntensityCurve {
+        curve: Curve::Square,
+        min: 0.0,
+        max: 1.0,
+    };
+
+    let mut frames = vec![[0; UNIVERSE_SIZE]];
+    color::write(&fixture, &mut frames, Rgb::WHITE, 0.5);
+    assert_eq!(frames[0][..4], [64, 255, 255, 255]);
+}
+
+#[test]
+fn extracts_white_and_amber() {
+    let fixture = fixture(&[
+        Attribute::Red,
+        Attribute::Green,
+        Attribute::Blue,
+        Attribute::White,
+        Attribute::Amber,
+    ]);
+    let warm_white = Rgb {
+        red: 1.0,
+        green: 0.8,
+        blue: 0.6,
+    };
+    let values = color::mix(&fixture, warm_white, 1.0);
+
+    assert_close(value(&values, Attribute::White), 0.6);
+    assert_close(value(&values, Attribute::Amber), 0.4);
+    assert_close(value(&values, Attribute::Red), 0.0);
+    assert_close(value(&values, Attribute::Green), 0.0);
+    assert_close(value(&values, Attribute::Blue), 0.0);
+}
+
+#[test]
+fn subtracts_with_cmy_filters() {
+    let fixture = fixture(&[
+        Attribute::Dimmer,
+        Attribute::Cyan,
+        Attribute::Magenta,
+        Attribute::Yellow,
+    ]);
+    let blue = Rgb {
+        red: 0.0,
+        green: 0.0,
+        blue: 1.0,
+    };
+    let values = color::mix(&fixture, blue, 1.0);
+
+    assert_close(value(&values, Attribute::Cyan), 1.0);
+    assert_close(value(&values, Attribute::Magenta), 1.0);
+    assert_close(value(&values, Attribute::Yellow), 0.0);
+}
+
+#[test]
+fn corrects_white_light_with_cto_and_ctb() {
+    let fixture = fixture(&[Attribute::Dimmer, Attribute::Cto, Attribute::Ctb]);
+    let warm = Rgb {
+        red: 1.0,
+        green: 0.7,
+        blue: 0.4,
+    };
+    let values = color::mix(&fixture, warm, 1.0);
+
+    assert_close(value(&values, Attribute::Cto), 0.6);
+    assert_close(value(&values, Attribute::Ctb), 0.0);
+}
@@ -0,0 +1,23 @@
+use blaulicht_lib::fixture::{
+    curve::IntensityCurve,
+    movement::Movement,
+    patch::ResolvedFixture,
+    profile::{Attribute, AttributeChannels},
+};
+
+/// A dimmer at the address of its id.
+pub fn fixture(id: u32) -> ResolvedFixture {
+    ResolvedFixture {
+        id,
+        universe: 0,
+        address: id as u16,
+        footprint: 1,
+        attributes: vec![AttributeChannels {
+            attribute: Attribute::Dimmer,
+            offsets: vec![0],
+            default_value: 0,
+        }],
+        movement: Movement::default(),
+        intensity_curve: IntensityCurve::default(),
+    }
+}
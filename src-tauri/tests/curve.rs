@@ -0,0 +1,78 @@
+use blaulicht_lib::fixture::curve::{Curve, IntensityCurve};
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 0.001,
+        "{actual} is not {expected}"
+    );
+}
+
+#[test]
+fn keeps_the_endpoints() {
+    for curve in [
+        Curve::Linear,
+        Curve::Square,
+        Curve::InverseSquare,
+        Curve::SCurve,
+    ] {
+        assert_close(curve.apply(0.0), 0.0);
+        assert_close(curve.apply(1.0), 1.0);
+    }
+}
+
+#[test]
+fn inverse_square_is_fast_at_the_low_end() {
+    let curve = Curve::InverseSquare;
+    assert_close(curve.apply(0.25), 0.5);
+    assert_close(curve.apply(0.01), 0.1);
+    assert_close(Curve::Square.apply(curve.apply(0.3)), 0.3);
+}
+
+#[test]
+fn s_curve_is_slow_at_both_ends() {
+    let curve = Curve::SCurve;
+    assert_close(curve.apply(0.5), 0.5);
+    assert_close(curve.apply(0.25), 0.15625);
+    assert_close(curve.apply(0.75), 0.84375);
+    // Symmetric around the center.
+    assert_close(curve.apply(0.1), 1.0 - curve.apply(0.9));
+}
+
+#[test]
+fn interpolates_custom_tables() {
+    let curve = Curve::Custom(vec![0.0, 0.2, 1.0]);
+    assert_close(curve.apply(0.0), 0.0);
+    assert_close(curve.apply(0.25), 0.1);
+    assert_close(curve.apply(0.5), 0.2);
+    assert_close(curve.apply(0.75), 0.6);
+    assert_close(curve.apply(1.0), 1.0);
+
+    // Tables may fall as well as rise.
+    let curve = Curve::Custom(vec![1.0, 0.0]);
+    assert_close(curve.apply(0.0), 1.0);
+    assert_close(curve.apply(0.3), 0.7);
+    assert_close(curve.apply(1.0), 0.0);
+}
+
+#[test]
+fn handles_short_custom_tables() {
+    assert_close(Curve::Custom(vec![]).apply(0.4), 0.4);
+    assert_close(Curve::Custom(vec![0.6]).apply(0.0), 0.6);
+    assert_close(Curve::Custom(vec![0.6]).apply(1.0), 0.6);
+}
+
+#[test]
+fn clamps_custom_tables_in_the_intensity_curve() {
+    let curve = IntensityCurve {
+        curve: Curve::Custom(vec![-1.0, 2.0]),
+        ..IntensityCurve::default()
+    };
+    assert_close(curve.apply(0.1), 0.0);
+    assert_close(curve.apply(0.9), 1.0);
+    // Zero stays off, whatever the table says.
+    let curve = IntensityCurve {
+        curve: Curve::Custom(vec![0.5, 1.0]),
+        ..IntensityCurve::default()
+    };
+    assert_close(curve.apply(0.0), 0.0);
+}
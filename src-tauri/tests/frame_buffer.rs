@@ -0,0 +1,85 @@
+use std::{sync::Arc, thread};
+
+use blaulicht_lib::dmx::{output::FrameBuffer, EffectOwners, UNIVERSE_SIZE};
+
+#[test]
+fn writes_stay_hidden_until_published() {
+    let buffer = FrameBuffer::new(2);
+    assert_eq!(buffer.snapshot(), vec![[0; UNIVERSE_SIZE]; 2]);
+
+    buffer.write(|frames| {
+        frames[0][0] = 255;
+        frames.push([7; UNIVERSE_SIZE]);
+    });
+    assert_eq!(buffer.read(0)[0], 0);
+    assert_eq!(buffer.snapshot().len(), 2);
+
+    buffer.publish();
+    assert_eq!(buffer.read(0)[0], 255);
+    assert_eq!(buffer.read(2), [7; UNIVERSE_SIZE]);
+    assert_eq!(buffer.snapshot().len(), 3);
+
+    // Universes that were never written read as silent.
+    assert_eq!(buffer.read(9), [0; UNIVERSE_SIZE]);
+}
+
+#[test]
+fn keeps_the_back_buffer_after_publishing() {
+    let buffer = FrameBuffer::new(1);
+    buffer.write(|frames| frames[0][0] = 10);
+    buffer.publish();
+
+    // The engine builds on its last frame, not on an empty one.
+    let previous = buffer.write(|frames| frames[0][0]);
+    assert_eq!(previous, 10);
+
+    buffer.write(|frames| frames[0][1] = 20);
+    let snapshot = buffer.snapshot();
+    assert_eq!(snapshot[0][..2], [10, 0]);
+
+    // Snapshots are copies, later publishes do not change them.
+    buffer.publish();
+    assert_eq!(snapshot[0][..2], [10, 0]);
+    assert_eq!(buffer.read(0)[..2], [10, 20]);
+}
+
+#[test]
+fn publishes_the_effect_owners_with_the_frames() {
+    let buffer = FrameBuffer::new(1);
+    buffer.write_effects(|effects| {
+        *effects = EffectOwners {
+            names: vec!["Wash (Base)".to_string()],
+            channels: vec![[None; UNIVERSE_SIZE]],
+        };
+        effects.channels[0][3] = Some(0);
+    });
+    assert!(buffer.snapshot_effects().names.is_empty());
+
+    buffer.publish();
+    let effects = buffer.snapshot_effects();
+    assert_eq!(effects.name(0, 3), Some("Wash (Base)"));
+    assert_eq!(effects.name(0, 4), None);
+    assert_eq!(effects.name(1, 3), None);
+}
+
+#[test]
+fn never_shows_a_half_written_frame() {
+    let buffer = Arc::new(FrameBuffer::new(1));
+
+    let writer = {
+        let buffer = buffer.clone();
+        thread::spawn(move || {
+            for value in 0..=255u8 {
+                buffer.write(|frames| frames[0].fill(value));
+                buffer.publish();
+            }
+        })
+    };
+
+    while !writer.is_finished() {
+        let frame = buffer.read(0);
+        assert!(frame.iter().all(|&v| v == frame[0]), "torn frame");
+    }
+    writer.join().unwrap();
+    assert_eq!(buffer.read(0), [255; UNIVERSE_SIZE]);
+}
@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+
+use blaulicht_lib::fixture::{
+    import::{self, gdtf},
+    profile::{Attribute, FixtureProfile},
+};
+
+fn sample_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn attributes(profile: &FixtureProfile, mode: &str) -> Vec<(Attribute, Vec<u16>)> {
+    profile
+        .attributes(profile.mode(mode).unwrap())
+        .into_iter()
+        .map(|a| (a.attribute, a.offsets))
+        .collect()
+}
+
+#[test]
+fn imports_modes_and_footprints() {
+    let report = gdtf::import(&sample_path("Generic-LED-Wash.gdtf")).unwrap();
+    let profile = &report.profile;
+    profile.validate().unwrap();
+
+    assert_eq!(profile.id(), "Generic/LED Wash Zoom");
+
+    let modes: Vec<&str> = profile.modes.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(modes, vec!["Extended", "Basic"]);
+
+    // The virtual dimmer takes up no address.
+    assert_eq!(profile.mode("Extended").unwrap().footprint(), 11);
+    // Offset 5 is left out, and the zoom on the second DMX break is not supported.
+    assert_eq!(
+        profile.mode("Basic").unwrap().channels,
+        vec![
+            "Beam Dimmer",
+            "Beam ColorAdd_R",
+            "Beam ColorAdd_G",
+            "Beam ColorAdd_B",
+            "Unused 5",
+            "Beam Shutter1",
+        ]
+    );
+
+    assert_eq!(
+        report.unmapped,
+        vec![
+            "Zoom: no matching attribute",
+            "Mode Basic: Zoom on a second DMX break",
+        ]
+    );
+}
+
+#[test]
+fn maps_attributes() {
+    let report = gdtf::import(&sample_path("Generic-LED-Wash.gdtf")).unwrap();
+    let profile = &report.profile;
+
+    assert_eq!(
+        attributes(profile, "Basic"),
+        vec![
+            (Attribute::Dimmer, vec![0]),
+            (Attribute::Red, vec![1]),
+            (Attribute::Green, vec![2]),
+            (Attribute::Blue, vec![3]),
+            (Attribute::Generic, vec![4]),
+            (Attribute::Strobe, vec![5]),
+        ]
+    );
+    assert_eq!(
+        profile.channel("Beam Zoom").unwrap().attribute,
+        Attribute::Generic
+    );
+
+    // Sub-attributes like the strobe of a shutter belong to their main attribute.
+    let shutter = profile.channel("Beam Shutter1").unwrap();
+    assert_eq!(shutter.default_value, 255);
+    let ranges: Vec<(u8, u8, &str)> = shutter
+        .ranges
+        .iter()
+        .map(|r| (r.from, r.to, r.name.as_str()))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![(0, 31, "Closed"), (32, 223, "Strobe"), (224, 255, "Open")]
+    );
+}
+
+#[test]
+fn imports_multi_byte_channels() {
+    let report = gdtf::import(&sample_path("Generic-LED-Wash.gdtf")).unwrap();
+    let profile = &report.profile;
+
+    let pan = profile.channel("Yoke Pan").unwrap();
+    assert_eq!(pan.fine_channels, vec!["Yoke Pan fine"]);
+    // The 16 bit default is cut down to its coarse byte.
+    assert_eq!(pan.default_value, 128);
+
+    let extended = attributes(profile, "Extended");
+    assert_eq!(extended[0], (Attribute::Pan, vec![0, 1]));
+    assert_eq!(extended[1], (Attribute::Tilt, vec![2, 3]));
+    assert_eq!(extended[2], (Attribute::Dimmer, vec![4, 5]));
+    assert_eq!(extended[3], (Attribute::Strobe, vec![6]));
+
+    // Without its fine channel in the mode, the dimmer is 8 bit.
+    assert_eq!(
+        attributes(profile, "Basic")[0],
+        (Attribute::Dimmer, vec![0])
+    );
+}
+
+#[test]
+fn picks_importer_by_extension() {
+    let report = import::import(&sample_path("Generic-LED-Wash.gdtf")).unwrap();
+    assert_eq!(report.profile.id(), "Generic/LED Wash Zoom");
+}
+
+#[test]
+fn rejects_archives_without_a_description() {
+    assert!(gdtf::read(std::io::Cursor::new(b"not a zip".to_vec())).is_err());
+    assert!(gdtf::parse("<GDTF/>").is_err());
+}
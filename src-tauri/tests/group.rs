@@ -0,0 +1,79 @@
+use blaulicht_lib::fixture::{
+    self,
+    group::{self, FixtureGroup, Selection},
+    patch::{self, PatchedFixture},
+};
+
+const FIXTURES: [u32; 5] = [1, 2, 3, 4, 5];
+
+fn group(name: &str, fixtures: &[u32]) -> FixtureGroup {
+    FixtureGroup {
+        name: name.to_string(),
+        fixtures: fixtures.to_vec(),
+    }
+}
+
+#[test]
+fn selects_fixtures() {
+    assert_eq!(Selection::Odd.apply(&FIXTURES), vec![1, 3, 5]);
+    assert_eq!(Selection::Even.apply(&FIXTURES), vec![2, 4]);
+    assert_eq!(Selection::Reverse.apply(&FIXTURES), vec![5, 4, 3, 2, 1]);
+    assert_eq!(Selection::First(2).apply(&FIXTURES), vec![1, 2]);
+    assert_eq!(Selection::Last(2).apply(&FIXTURES), vec![4, 5]);
+
+    let mut shuffled = Selection::Shuffle.apply(&FIXTURES);
+    shuffled.sort_unstable();
+    assert_eq!(shuffled, FIXTURES);
+}
+
+#[test]
+fn selects_at_most_every_fixture() {
+    assert_eq!(Selection::First(10).apply(&FIXTURES), FIXTURES);
+    assert_eq!(Selection::Last(10).apply(&FIXTURES), FIXTURES);
+    assert!(Selection::Last(0).apply(&FIXTURES).is_empty());
+    assert!(Selection::Even.apply(&[1]).is_empty());
+    assert!(Selection::Odd.apply(&[]).is_empty());
+}
+
+#[test]
+fn spreads_fixtures_over_a_cycle() {
+    let spread: Vec<f32> = (0..4).map(|i| group::spread(i, 4)).collect();
+    assert_eq!(spread, vec![0.0, 0.25, 0.5, 0.75]);
+    assert_eq!(group::spread(0, 1), 0.0);
+    assert_eq!(group::spread(0, 0), 0.0);
+}
+
+#[test]
+fn resolves_patched_fixtures_in_group_order() {
+    let patch: Vec<PatchedFixture> = [(1, 1), (2, 5), (3, 9)]
+        .map(|(id, address)| PatchedFixture {
+            id,
+            name: format!("Par {id}"),
+            profile: "Generic/RGB PAR".to_string(),
+            mode: "4 channel".to_string(),
+            universe: 0,
+            address,
+            position: None,
+            movement: Default::default(),
+            intensity_curve: Default::default(),
+        })
+        .to_vec();
+    let fixtures = patch::resolve(&patch, &fixture::builtin());
+
+    // Fixture 4 is not patched.
+    let resolved = group("Pars", &[3, 4, 1]).resolve(&fixtures);
+    let ids: Vec<u32> = resolved.iter().map(|f| f.id).collect();
+    assert_eq!(ids, vec![3, 1]);
+}
+
+#[test]
+fn validates_group_names() {
+    assert!(group::validate(&[group("Left", &[1]), group("Right", &[2])]).is_ok());
+    assert!(group::validate(&[]).is_ok());
+
+    assert!(group::validate(&[group(" ", &[1])]).is_err());
+    assert_eq!(
+        group::validate(&[group("Left", &[1]), group("Left", &[2])]),
+        Err("There is more than one group named Left".to_string())
+    );
+}
@@ -0,0 +1,135 @@
+use std::{fs, path::PathBuf};
+
+use blaulicht_lib::dmx::interfaces::{
+    self, default_interfaces, DmxTiming, InterfaceDefinition, Protocol,
+};
+use serialport::{SerialPortInfo, SerialPortType, UsbPortInfo};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("blaulicht-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn usb(vid: u16, pid: u16, serial_number: Option<&str>) -> UsbPortInfo {
+    UsbPortInfo {
+        vid,
+        pid,
+        serial_number: serial_number.map(str::to_string),
+        manufacturer: None,
+        product: None,
+    }
+}
+
+fn port(name: &str, port_type: SerialPortType) -> SerialPortInfo {
+    SerialPortInfo {
+        port_name: name.to_string(),
+        port_type,
+    }
+}
+
+fn enttec(serial_number: Option<&str>) -> InterfaceDefinition {
+    InterfaceDefinition {
+        name: "ENTTEC DMX USB Pro".to_string(),
+        vid: 0x0403,
+        pid: 0x6001,
+        serial_number: serial_number.map(str::to_string),
+        protocol: Protocol::EnttecPro,
+        timing: DmxTiming::default(),
+    }
+}
+
+#[test]
+fn matches_vendor_and_product_ids() {
+    let definition = enttec(None);
+    assert!(definition.matches(&usb(0x0403, 0x6001, None)));
+    assert!(definition.matches(&usb(0x0403, 0x6001, Some("EN123"))));
+    assert!(!definition.matches(&usb(0x0403, 0x6010, None)));
+    assert!(!definition.matches(&usb(0x10c4, 0x6001, None)));
+}
+
+#[test]
+fn matches_the_serial_number_if_given() {
+    let definition = enttec(Some("EN123"));
+    assert!(definition.matches(&usb(0x0403, 0x6001, Some("EN123"))));
+    assert!(!definition.matches(&usb(0x0403, 0x6001, Some("EN456"))));
+    assert!(!definition.matches(&usb(0x0403, 0x6001, None)));
+}
+
+#[test]
+fn round_trips_the_registry() {
+    let dir = scratch_dir("interfaces");
+    let path = dir.join("config").join(interfaces::INTERFACES_FILE);
+    let definitions = vec![
+        enttec(Some("EN123")),
+        InterfaceDefinition {
+            timing: DmxTiming {
+                break_us: 176,
+                slots: 24,
+                ..DmxTiming::default()
+            },
+            ..default_interfaces()[0].clone()
+        },
+    ];
+
+    interfaces::save(&path, &definitions).unwrap();
+    assert_eq!(interfaces::load(&path), definitions);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn falls_back_to_the_defaults() {
+    let dir = scratch_dir("interfaces-defaults");
+    let path = dir.join(interfaces::INTERFACES_FILE);
+    assert_eq!(interfaces::load(&path), default_interfaces());
+
+    fs::write(&path, "[{ not json").unwrap();
+    assert_eq!(interfaces::load(&path), default_interfaces());
+
+    // Missing timing fields take their defaults.
+    fs::write(
+        &path,
+        r#"[{ "name": "Cable", "vid": 1, "pid": 2, "protocol": "OpenDmx", "timing": { "slots": 32 } }]"#,
+    )
+    .unwrap();
+    let loaded = interfaces::load(&path);
+    assert_eq!(loaded[0].serial_number, None);
+    assert_eq!(
+        loaded[0].timing,
+        DmxTiming {
+            slots: 32,
+            ..DmxTiming::default()
+        }
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn finds_the_first_matching_usb_port() {
+    let definitions = vec![enttec(Some("EN456")), enttec(None)];
+    let ports = vec![
+        port("/dev/ttyS0", SerialPortType::Unknown),
+        port(
+            "/dev/ttyUSB0",
+            SerialPortType::UsbPort(usb(0x10c4, 0xea60, None)),
+        ),
+        port(
+            "/dev/ttyUSB1",
+            SerialPortType::UsbPort(usb(0x0403, 0x6001, Some("EN123"))),
+        ),
+        port(
+            "/dev/ttyUSB2",
+            SerialPortType::UsbPort(usb(0x0403, 0x6001, Some("EN456"))),
+        ),
+    ];
+
+    let (found, definition) = interfaces::first_match(&definitions, ports).unwrap();
+    assert_eq!(found.port_name, "/dev/ttyUSB1");
+    assert_eq!(definition, enttec(None));
+
+    let ports = vec![port("/dev/ttyACM0", SerialPortType::PciPort)];
+    assert_eq!(interfaces::first_match(&definitions, ports), None);
+}
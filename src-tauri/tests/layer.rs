@@ -0,0 +1,90 @@
+use blaulicht_lib::{
+    engine::{
+        effect::Values,
+        layer::{self, BlendMode, Layer},
+    },
+    fixture::profile::Attribute,
+};
+
+fn layer(blend: BlendMode, opacity: f32) -> Layer {
+    Layer {
+        name: "Top".to_string(),
+        opacity,
+        blend,
+    }
+}
+
+fn below() -> Values {
+    let mut values = Values::default();
+    values.set(1, Attribute::Dimmer, 0.6);
+    values.set(1, Attribute::Red, 1.0);
+    values.set(2, Attribute::Dimmer, 0.2);
+    values
+}
+
+fn blend(layer: &Layer, dimmer: f32) -> Values {
+    let mut top = Values::default();
+    top.set(1, Attribute::Dimmer, dimmer);
+    top.set(2, Attribute::Dimmer, dimmer);
+
+    let mut values = below();
+    layer.blend(&top, &mut values);
+    values
+}
+
+#[test]
+fn blends_each_mode() {
+    let htp = blend(&layer(BlendMode::Htp, 1.0), 0.4);
+    assert_eq!(htp.get(1, Attribute::Dimmer), Some(0.6));
+    assert_eq!(htp.get(2, Attribute::Dimmer), Some(0.4));
+
+    let ltp = blend(&layer(BlendMode::Ltp, 1.0), 0.4);
+    assert_eq!(ltp.get(1, Attribute::Dimmer), Some(0.4));
+    assert_eq!(ltp.get(1, Attribute::Red), Some(1.0));
+
+    let add = blend(&layer(BlendMode::Add, 1.0), 0.5);
+    assert_eq!(add.get(1, Attribute::Dimmer), Some(1.0));
+    assert_eq!(add.get(2, Attribute::Dimmer), Some(0.7));
+
+    let multiply = blend(&layer(BlendMode::Multiply, 1.0), 0.5);
+    assert_eq!(multiply.get(1, Attribute::Dimmer), Some(0.3));
+
+    let over = blend(&layer(BlendMode::Override, 1.0), 0.4);
+    assert_eq!(over.get(1, Attribute::Dimmer), Some(0.4));
+    assert_eq!(over.get(1, Attribute::Red), None);
+}
+
+#[test]
+fn owns_the_attributes_it_decides() {
+    let mut top = Values::default();
+    top.set(1, Attribute::Dimmer, 0.4);
+    top.set(2, Attribute::Dimmer, 0.4);
+
+    let mut owned = layer(BlendMode::Htp, 1.0).blend(&top, &mut below());
+    owned.sort_by_key(|&(fixture, _)| fixture);
+    assert_eq!(owned, vec![(2, Attribute::Dimmer)]);
+
+    let owned = layer(BlendMode::Ltp, 1.0).blend(&top, &mut below());
+    assert_eq!(owned.len(), 2);
+
+    let owned = layer(BlendMode::Multiply, 1.0).blend(&top, &mut below());
+    assert!(owned.is_empty());
+
+    let owned = layer(BlendMode::Ltp, 0.0).blend(&top, &mut below());
+    assert!(owned.is_empty());
+}
+
+#[test]
+fn opacity_fades_between_layers() {
+    let half = blend(&layer(BlendMode::Ltp, 0.5), 0.0);
+    assert_eq!(half.get(1, Attribute::Dimmer), Some(0.3));
+
+    let off = blend(&layer(BlendMode::Ltp, 0.0), 0.0);
+    assert_eq!(off.get(1, Attribute::Dimmer), Some(0.6));
+}
+
+#[test]
+fn rejects_duplicate_layer_names() {
+    assert!(layer::validate(&[Layer::default(), layer(BlendMode::Htp, 1.0)]).is_ok());
+    assert!(layer::validate(&[Layer::default(), Layer::default()]).is_err());
+}
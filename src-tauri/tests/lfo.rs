@@ -0,0 +1,83 @@
+mod common;
+
+use std::time::Duration;
+
+use blaulicht_lib::{
+    engine::{
+        analysis::Analysis,
+        effect::{
+            lfo::{Lfo, LfoConfig, Rate, Shape},
+            Clock, Context, Effect, Values,
+        },
+    },
+    fixture::{patch::ResolvedFixture, profile::Attribute},
+};
+
+use common::fixture;
+
+fn render(lfo: &mut Lfo, fixtures: &[&ResolvedFixture], analysis: &Analysis, delta: f32) -> Values {
+    let context = Context {
+        analysis,
+        clock: Clock {
+            time: Duration::ZERO,
+            delta: Duration::from_secs_f32(delta),
+        },
+    };
+    let mut values = Values::default();
+    lfo.render(&context, fixtures, &mut values);
+    values
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 0.001,
+        "{actual} is not {expected}"
+    );
+}
+
+#[test]
+fn spreads_the_phase_over_the_group() {
+    let rig: Vec<ResolvedFixture> = (1..=4).map(fixture).collect();
+    let fixtures: Vec<&ResolvedFixture> = rig.iter().collect();
+    let mut lfo = Lfo::new(LfoConfig {
+        shape: Shape::Sine,
+        rate: Rate::Hz(1.0),
+        attribute: Attribute::Dimmer,
+    });
+    lfo.params_mut().set("spread", 1.0).unwrap();
+
+    // A quarter cycle in, the first fixture is at the top of the wave and every
+    // following one a quarter cycle behind.
+    let values = render(&mut lfo, &fixtures, &Analysis::default(), 0.25);
+    let levels = [1, 2, 3, 4].map(|id| values.get(id, Attribute::Dimmer).unwrap());
+    for (level, expected) in levels.into_iter().zip([1.0, 0.5, 0.0, 0.5]) {
+        assert_close(level, expected);
+    }
+}
+
+#[test]
+fn locks_to_beats_and_holds_samples() {
+    let rig = [fixture(1)];
+    let fixtures: Vec<&ResolvedFixture> = rig.iter().collect();
+    let mut lfo = Lfo::new(LfoConfig {
+        shape: Shape::SampleAndHold,
+        rate: Rate::Beats(2.0),
+        attribute: Attribute::Pan,
+    });
+
+    let quiet = Analysis::default();
+    let beat = Analysis {
+        beat: true,
+        ..Analysis::default()
+    };
+
+    let first = render(&mut lfo, &fixtures, &quiet, 1.0).get(1, Attribute::Pan);
+    let first = first.unwrap();
+    assert!((-45.0..=45.0).contains(&first), "{first}");
+
+    // Time alone does not move a beat locked wave, and one beat is half a cycle.
+    for analysis in [&quiet, &beat] {
+        let held = render(&mut lfo, &fixtures, analysis, 1.0).get(1, Attribute::Pan);
+        assert_eq!(held, Some(first));
+    }
+}
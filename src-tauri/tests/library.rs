@@ -0,0 +1,132 @@
+use std::{fs, path::PathBuf};
+
+use blaulicht_lib::fixture::{
+    self,
+    profile::{encode, Attribute},
+};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("blaulicht-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+const JSON_PROFILE: &str = r#"{
+  "manufacturer": "Test",
+  "name": "Spot",
+  "channels": [
+    { "name": "Pan", "attribute": "pan", "fineChannels": ["Pan fine"], "defaultValue": 128 },
+    { "name": "Pan fine", "attribute": "generic" },
+    { "name": "Dimmer", "attribute": "dimmer" }
+  ],
+  "modes": [
+    { "name": "3 channel", "channels": ["Pan", "Pan fine", "Dimmer"] }
+  ]
+}"#;
+
+const TOML_PROFILE: &str = r#"
+manufacturer = "Test"
+name = "Par"
+
+[[channels]]
+name = "Red"
+attribute = "red"
+
+[[channels]]
+name = "Green"
+attribute = "green"
+
+[[channels]]
+name = "Blue"
+attribute = "blue"
+
+[[modes]]
+name = "3 channel"
+channels = ["Red", "Green", "Blue"]
+"#;
+
+#[test]
+fn encodes_the_most_significant_byte_first() {
+    let mut one = [0; 1];
+    encode(1.0, &mut one);
+    assert_eq!(one, [255]);
+    encode(0.5, &mut one);
+    assert_eq!(one, [128]);
+
+    let mut two = [0; 2];
+    encode(0.5, &mut two);
+    assert_eq!(two, [0x80, 0x00]);
+    encode(0.25, &mut two);
+    assert_eq!(two, [0x40, 0x00]);
+    encode(1.0, &mut two);
+    assert_eq!(two, [0xff, 0xff]);
+
+    let mut three = [0; 3];
+    encode(1.0 / 3.0, &mut three);
+    assert_eq!(three, [0x55, 0x55, 0x55]);
+}
+
+#[test]
+fn encode_clamps_the_value() {
+    let mut two = [0; 2];
+    encode(2.0, &mut two);
+    assert_eq!(two, [0xff, 0xff]);
+    encode(-1.0, &mut two);
+    assert_eq!(two, [0x00, 0x00]);
+}
+
+#[test]
+fn loads_json_and_toml_profiles() {
+    let dir = scratch_dir("library");
+    fs::write(dir.join("spot.json"), JSON_PROFILE).unwrap();
+    fs::write(dir.join("par.toml"), TOML_PROFILE).unwrap();
+
+    let library = fixture::load_library(&dir);
+    let spot = library.iter().find(|p| p.id() == "Test/Spot").unwrap();
+    let pan = spot.channel("Pan").unwrap();
+    assert_eq!(pan.attribute, Attribute::Pan);
+    assert_eq!(pan.fine_channels, vec!["Pan fine".to_string()]);
+    assert_eq!(pan.default_value, 128);
+
+    let par = library.iter().find(|p| p.id() == "Test/Par").unwrap();
+    assert_eq!(par.mode("3 channel").unwrap().footprint(), 3);
+
+    // The builtin profiles are always there.
+    assert!(library.iter().any(|p| p.id() == "Generic/Dimmer"));
+}
+
+#[test]
+fn skips_broken_profiles_and_replaces_builtin_ones() {
+    let dir = scratch_dir("library-overrides");
+    fs::write(dir.join("broken.json"), "{ not a profile").unwrap();
+    fs::write(dir.join("notes.txt"), "not a profile either").unwrap();
+    fs::write(
+        dir.join("dimmer.json"),
+        r#"{
+          "manufacturer": "Generic",
+          "name": "Dimmer",
+          "channels": [
+            { "name": "Dimmer", "attribute": "dimmer" },
+            { "name": "Dimmer fine", "attribute": "generic" }
+          ],
+          "modes": [{ "name": "2 channel", "channels": ["Dimmer", "Dimmer fine"] }]
+        }"#,
+    )
+    .unwrap();
+
+    let library = fixture::load_library(&dir);
+    assert_eq!(library.len(), fixture::builtin().len());
+    let dimmers: Vec<_> = library
+        .iter()
+        .filter(|p| p.id() == "Generic/Dimmer")
+        .collect();
+    assert_eq!(dimmers.len(), 1);
+    assert!(dimmers[0].mode("2 channel").is_some());
+}
+
+#[test]
+fn a_missing_library_has_the_builtin_profiles() {
+    let dir = scratch_dir("library-missing").join("fixtures");
+    assert_eq!(fixture::load_library(&dir), fixture::builtin());
+}
@@ -0,0 +1,73 @@
+use blaulicht_lib::dmx::{manual::ManualOverrides, Frame, Owner, Owners, UNIVERSE_SIZE};
+
+/// Engine output at 10 on every channel.
+fn engine_output(universes: usize) -> (Vec<Frame>, Vec<Owners>) {
+    (
+        vec![[10; UNIVERSE_SIZE]; universes],
+        vec![[Owner::Engine; UNIVERSE_SIZE]; universes],
+    )
+}
+
+#[test]
+fn overrides_the_engine_output() {
+    let mut overrides = ManualOverrides::default();
+    overrides.set(0, 3, &[200, 0, 255]);
+
+    let (mut frames, mut owners) = engine_output(1);
+    overrides.apply(&mut frames, &mut owners);
+
+    assert_eq!(frames[0][..6], [10, 10, 200, 0, 255, 10]);
+    assert_eq!(owners[0][1], Owner::Engine);
+    assert_eq!(owners[0][2..5], [Owner::Manual; 3]);
+    assert_eq!(owners[0][5], Owner::Engine);
+}
+
+#[test]
+fn ignores_values_beyond_the_universe() {
+    let mut overrides = ManualOverrides::default();
+    overrides.set(0, 511, &[1, 2, 3, 4]);
+    // Channel 0 is taken as channel 1.
+    overrides.set(0, 0, &[5]);
+
+    let (mut frames, mut owners) = engine_output(1);
+    overrides.apply(&mut frames, &mut owners);
+
+    assert_eq!(frames[0][0], 5);
+    assert_eq!(frames[0][510..], [1, 2]);
+}
+
+#[test]
+fn grows_the_frames_to_the_universe() {
+    let mut overrides = ManualOverrides::default();
+    overrides.set(2, 1, &[42]);
+
+    let (mut frames, mut owners) = engine_output(1);
+    overrides.apply(&mut frames, &mut owners);
+
+    assert_eq!(frames.len(), 3);
+    assert_eq!(owners.len(), 3);
+    assert_eq!(frames[2][..2], [42, 0]);
+    assert_eq!(owners[2][..2], [Owner::Manual, Owner::Engine]);
+}
+
+#[test]
+fn releases_ranges_back_to_the_engine() {
+    let mut overrides = ManualOverrides::default();
+    overrides.set(0, 1, &[100; 8]);
+    overrides.release(0, 3, 4);
+
+    let (mut frames, mut owners) = engine_output(1);
+    overrides.apply(&mut frames, &mut owners);
+
+    assert_eq!(frames[0][..8], [100, 100, 10, 10, 10, 10, 100, 100]);
+    assert_eq!(owners[0][2..6], [Owner::Engine; 4]);
+
+    // Releasing an untouched universe does nothing.
+    overrides.release(5, 1, UNIVERSE_SIZE as u16);
+
+    overrides.release_all();
+    let (mut frames, mut owners) = engine_output(1);
+    overrides.apply(&mut frames, &mut owners);
+    assert_eq!(frames, engine_output(1).0);
+    assert_eq!(owners, engine_output(1).1);
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use blaulicht_lib::{
+    engine::{
+        analysis::Analysis,
+        modulation::{self, Matrix, Route, Source},
+    },
+    fixture::curve::Curve,
+};
+
+fn route(source: Source) -> Route {
+    Route {
+        source,
+        effect: "Wash".to_string(),
+        param: "intensity".to_string(),
+        min: 0.2,
+        max: 1.0,
+        curve: Curve::Linear,
+        smoothing: 0.0,
+        invert: false,
+    }
+}
+
+fn value(matrix: &Matrix) -> f32 {
+    matrix.values().next().unwrap().1
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 0.001,
+        "{actual} is not {expected}"
+    );
+}
+
+#[test]
+fn scales_curves_and_inverts() {
+    let analysis = Analysis {
+        bands: vec![0.5],
+        ..Analysis::default()
+    };
+    let mut matrix = Matrix::default();
+
+    matrix.set_routes(vec![route(Source::Band(0))]);
+    matrix.update(&analysis, Duration::ZERO);
+    assert_close(value(&matrix), 0.6);
+
+    matrix.set_routes(vec![Route {
+        curve: Curve::Square,
+        ..route(Source::Band(0))
+    }]);
+    matrix.update(&analysis, Duration::ZERO);
+    assert_close(value(&matrix), 0.4);
+
+    matrix.set_routes(vec![Route {
+        invert: true,
+        ..route(Source::Silence)
+    }]);
+    matrix.update(&analysis, Duration::ZERO);
+    assert_close(value(&matrix), 1.0);
+}
+
+#[test]
+fn smooths_towards_the_source() {
+    let beat = Analysis {
+        beat: true,
+        ..Analysis::default()
+    };
+    let mut matrix = Matrix::default();
+    matrix.set_routes(vec![Route {
+        min: 0.0,
+        smoothing: 1.0,
+        ..route(Source::Beat)
+    }]);
+
+    matrix.update(&beat, Duration::from_secs(1));
+    assert_close(value(&matrix), 1.0 - (-1.0f32).exp());
+
+    // Without beats the level decays again.
+    let level = value(&matrix);
+    matrix.update(&Analysis::default(), Duration::from_millis(500));
+    assert!(value(&matrix) < level);
+}
+
+#[test]
+fn counts_beats_of_a_bar() {
+    let mut matrix = Matrix::default();
+    matrix.set_routes(vec![Route {
+        min: 0.0,
+        ..route(Source::BeatIndex(4))
+    }]);
+
+    let levels: Vec<f32> = (0..5)
+        .map(|beat_index| {
+            let analysis = Analysis {
+                beat_index,
+                ..Analysis::default()
+            };
+            matrix.update(&analysis, Duration::ZERO);
+            value(&matrix)
+        })
+        .collect();
+    assert_eq!(levels, [0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0, 0.0]);
+}
+
+#[test]
+fn rejects_routes_without_target() {
+    assert!(modulation::validate(&[route(Source::Volume)]).is_ok());
+
+    let untargeted = Route {
+        effect: String::new(),
+        ..route(Source::Volume)
+    };
+    assert!(modulation::validate(&[untargeted]).is_err());
+}
@@ -0,0 +1,118 @@
+use std::thread;
+
+use blaulicht_lib::dmx::{
+    monitor::{Monitor, UniverseUpdate, PUBLISH_INTERVAL},
+    EffectOwners, Frame, Owner, Owners, UNIVERSE_SIZE,
+};
+
+fn engine() -> Vec<Owners> {
+    vec![[Owner::Engine; UNIVERSE_SIZE]]
+}
+
+/// Waits for the throttle, then publishes the frames.
+fn publish(monitor: &mut Monitor, frames: &[Frame], owners: &[Owners]) -> Vec<UniverseUpdate> {
+    thread::sleep(PUBLISH_INTERVAL);
+    monitor.update(frames, owners, &EffectOwners::default())
+}
+
+fn changed(update: &UniverseUpdate) -> Vec<(u16, u8)> {
+    update
+        .channels
+        .iter()
+        .map(|c| (c.channel, c.value))
+        .collect()
+}
+
+#[test]
+fn sends_every_channel_first() {
+    let mut monitor = Monitor::new();
+    let updates = publish(&mut monitor, &[[7; UNIVERSE_SIZE]; 2], &engine());
+
+    assert_eq!(updates.len(), 2);
+    for (universe, update) in updates.iter().enumerate() {
+        assert_eq!(update.universe, universe as u16);
+        assert!(update.full);
+        assert_eq!(update.channels.len(), UNIVERSE_SIZE);
+    }
+    // Universes without owners count as the engine's.
+    assert_eq!(updates[1].channels[0].owner, Owner::Engine);
+}
+
+#[test]
+fn sends_only_changed_channels() {
+    let mut monitor = Monitor::new();
+    let mut frame = [0; UNIVERSE_SIZE];
+    publish(&mut monitor, &[frame], &engine());
+
+    // Nothing changed, nothing to send.
+    assert!(publish(&mut monitor, &[frame], &engine()).is_empty());
+
+    frame[0] = 255;
+    frame[511] = 1;
+    let updates = publish(&mut monitor, &[frame], &engine());
+    assert_eq!(updates.len(), 1);
+    assert!(!updates[0].full);
+    assert_eq!(changed(&updates[0]), vec![(1, 255), (512, 1)]);
+
+    // A new owner is a change even if the value stays the same.
+    let mut owners = engine();
+    owners[0][1] = Owner::Manual;
+    let updates = publish(&mut monitor, &[frame], &owners);
+    assert_eq!(changed(&updates[0]), vec![(2, 0)]);
+    assert_eq!(updates[0].channels[0].owner, Owner::Manual);
+}
+
+#[test]
+fn names_the_effects_of_engine_channels() {
+    let mut monitor = Monitor::new();
+    let mut channels = [None; UNIVERSE_SIZE];
+    channels[0] = Some(0);
+    channels[1] = Some(0);
+    let effects = EffectOwners {
+        names: vec!["Wash (Base)".to_string()],
+        channels: vec![channels],
+    };
+    let mut owners = engine();
+    owners[0][1] = Owner::Manual;
+
+    thread::sleep(PUBLISH_INTERVAL);
+    let updates = monitor.update(&[[0; UNIVERSE_SIZE]], &owners, &effects);
+
+    let effects: Vec<Option<&str>> = updates[0].channels[..3]
+        .iter()
+        .map(|c| c.effect.as_deref())
+        .collect();
+    assert_eq!(effects, vec![Some("Wash (Base)"), None, None]);
+}
+
+#[test]
+fn sends_everything_again_after_a_resync() {
+    let mut monitor = Monitor::new();
+    let frames = [[3; UNIVERSE_SIZE]];
+    publish(&mut monitor, &frames, &engine());
+
+    monitor.resync();
+    let updates = publish(&mut monitor, &frames, &engine());
+    assert!(updates[0].full);
+    assert_eq!(updates[0].channels.len(), UNIVERSE_SIZE);
+}
+
+#[test]
+fn throttles_updates() {
+    let mut monitor = Monitor::new();
+    let frames = [[1; UNIVERSE_SIZE]];
+    assert!(monitor
+        .update(&frames, &engine(), &EffectOwners::default())
+        .is_empty());
+
+    assert_eq!(publish(&mut monitor, &frames, &engine()).len(), 1);
+
+    // Changes right after a publish wait for the next one.
+    let frames = [[2; UNIVERSE_SIZE]];
+    assert!(monitor
+        .update(&frames, &engine(), &EffectOwners::default())
+        .is_empty());
+    let updates = publish(&mut monitor, &frames, &engine());
+    assert_eq!(updates[0].channels.len(), UNIVERSE_SIZE);
+    assert!(!updates[0].full);
+}
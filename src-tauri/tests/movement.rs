@@ -0,0 +1,131 @@
+use blaulicht_lib::{
+    dmx::UNIVERSE_SIZE,
+    fixture::{
+        curve::IntensityCurve,
+        movement::{self, Movement},
+        patch::ResolvedFixture,
+        profile::{encode, Attribute, AttributeChannels},
+    },
+};
+
+/// A moving head with a 16-bit pan and an 8-bit tilt channel.
+fn head(movement: Movement) -> ResolvedFixture {
+    ResolvedFixture {
+        id: 1,
+        universe: 0,
+        address: 1,
+        footprint: 3,
+        attributes: vec![
+            AttributeChannels {
+                attribute: Attribute::Pan,
+                offsets: vec![0, 1],
+                default_value: 0,
+            },
+            AttributeChannels {
+                attribute: Attribute::Tilt,
+                offsets: vec![2],
+                default_value: 0,
+            },
+        ],
+        movement,
+        intensity_curve: IntensityCurve::default(),
+    }
+}
+
+fn assert_close((pan, tilt): (f32, f32), (expected_pan, expected_tilt): (f32, f32)) {
+    assert!(
+        (pan - expected_pan).abs() < 0.001 && (tilt - expected_tilt).abs() < 0.001,
+        "({pan}, {tilt}) is not ({expected_pan}, {expected_tilt})"
+    );
+}
+
+#[test]
+fn centers_at_zero_degrees() {
+    let movement = Movement::default();
+    assert_close(movement.channel_values(0.0, 0.0), (0.5, 0.5));
+    assert_close(movement.channel_values(135.0, -67.5), (0.75, 0.25));
+    assert_close(movement.channel_values(270.0, 135.0), (1.0, 1.0));
+}
+
+#[test]
+fn inverts_each_axis() {
+    let movement = Movement {
+        invert_pan: true,
+        ..Movement::default()
+    };
+    assert_close(movement.channel_values(135.0, 67.5), (0.25, 0.75));
+
+    let movement = Movement {
+        invert_tilt: true,
+        ..Movement::default()
+    };
+    assert_close(movement.channel_values(135.0, 67.5), (0.75, 0.25));
+}
+
+#[test]
+fn swaps_pan_and_tilt() {
+    let movement = Movement {
+        swap: true,
+        ..Movement::default()
+    };
+    // Tilt drives the pan channel, pan the tilt channel.
+    assert_close(movement.channel_values(67.5, 135.0), (0.75, 0.75));
+    assert_close(movement.channel_values(0.0, 135.0), (0.75, 0.5));
+}
+
+#[test]
+fn stops_at_the_soft_limits() {
+    let movement = Movement {
+        pan_min: -90.0,
+        pan_max: 90.0,
+        tilt_min: 0.0,
+        tilt_max: 45.0,
+        ..Movement::default()
+    };
+    assert_close(movement.channel_values(180.0, -90.0), (2.0 / 3.0, 0.5));
+    assert_close(
+        movement.channel_values(-180.0, 90.0),
+        (1.0 / 3.0, 2.0 / 3.0),
+    );
+
+    // The limits apply after inverting.
+    let movement = Movement {
+        invert_pan: true,
+        ..movement
+    };
+    assert_close(movement.channel_values(180.0, 0.0), (1.0 / 3.0, 0.5));
+
+    // Limits the wrong way round don't panic.
+    let movement = Movement {
+        pan_min: 90.0,
+        pan_max: -90.0,
+        ..Movement::default()
+    };
+    movement.channel_values(0.0, 0.0);
+}
+
+#[test]
+fn stays_centered_without_a_range() {
+    let movement = Movement {
+        pan_range: 0.0,
+        ..Movement::default()
+    };
+    assert_close(movement.channel_values(90.0, 0.0), (0.5, 0.5));
+}
+
+#[test]
+fn writes_the_fine_channel() {
+    let fixture = head(Movement::default());
+    let mut frames = vec![[0; UNIVERSE_SIZE]];
+
+    movement::write(&fixture, &mut frames, 100.0, 0.0);
+
+    let (pan, _) = fixture.movement.channel_values(100.0, 0.0);
+    let mut expected = [0; 2];
+    encode(pan, &mut expected);
+    assert_ne!(expected[1], 0);
+    assert_eq!(frames[0][..3], [expected[0], expected[1], 128]);
+
+    movement::write(&fixture, &mut frames, 270.0, 135.0);
+    assert_eq!(frames[0][..3], [0xff, 0xff, 0xff]);
+}
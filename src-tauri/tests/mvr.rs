@@ -0,0 +1,124 @@
+use std::{fs, path::PathBuf};
+
+use blaulicht_lib::fixture::{
+    self,
+    curve::IntensityCurve,
+    movement::Movement,
+    mvr,
+    patch::{PatchedFixture, Position},
+};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("blaulicht-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn fixture(id: u32, mode: &str, universe: u16, address: u16) -> PatchedFixture {
+    PatchedFixture {
+        id,
+        name: format!("PAR {id}"),
+        profile: "Generic/RGB PAR".to_string(),
+        mode: mode.to_string(),
+        universe,
+        address,
+        position: Some(Position {
+            x: id as f32 * 1000.0,
+            y: -500.0,
+            z: 4500.0,
+        }),
+        movement: Movement::default(),
+        intensity_curve: IntensityCurve::default(),
+    }
+}
+
+#[test]
+fn round_trips_a_patch() {
+    let dir = scratch_dir("mvr-round-trip");
+    let path = dir.join("rig.mvr");
+    let patch = vec![
+        fixture(1, "4 channel", 0, 1),
+        fixture(2, "3 channel", 0, 5),
+        fixture(7, "4 channel", 1, 509),
+    ];
+
+    mvr::export(&path, &patch, &fixture::builtin(), &dir).unwrap();
+    let rig = mvr::import(&path).unwrap();
+
+    assert!(rig.warnings.is_empty(), "{:?}", rig.warnings);
+    assert_eq!(rig.gdtf_files.len(), 1);
+
+    // The generated GDTF file names its channels after their geometry.
+    let profile = &rig.gdtf_files[0].report.profile;
+    assert_eq!(profile.id(), "Generic/RGB PAR");
+    assert_eq!(profile.mode("4 channel").unwrap().footprint(), 4);
+    assert_eq!(profile.mode("3 channel").unwrap().footprint(), 3);
+
+    let imported: Vec<(u32, &str, u16, u16, Option<Position>)> = rig
+        .patch
+        .iter()
+        .map(|f| (f.id, f.mode.as_str(), f.universe, f.address, f.position))
+        .collect();
+    let expected: Vec<(u32, &str, u16, u16, Option<Position>)> = patch
+        .iter()
+        .map(|f| (f.id, f.mode.as_str(), f.universe, f.address, f.position))
+        .collect();
+    assert_eq!(imported, expected);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn gives_duplicate_ids_the_lowest_free_one() {
+    let dir = scratch_dir("mvr-ids");
+    let path = dir.join("rig.mvr");
+    let patch = vec![
+        fixture(u32::MAX, "4 channel", 0, 1),
+        fixture(u32::MAX, "4 channel", 0, 5),
+    ];
+
+    mvr::export(&path, &patch, &fixture::builtin(), &dir).unwrap();
+    let rig = mvr::import(&path).unwrap();
+
+    // The duplicate id is replaced by the lowest free one.
+    let ids: Vec<u32> = rig.patch.iter().map(|f| f.id).collect();
+    assert_eq!(ids, vec![u32::MAX, 1]);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn keeps_profiles_with_the_same_file_name_apart() {
+    let dir = scratch_dir("mvr-file-names");
+    let path = dir.join("rig.mvr");
+
+    let mut profiles = fixture::builtin();
+    let mut other = profiles
+        .iter()
+        .find(|p| p.id() == "Generic/RGB PAR")
+        .unwrap()
+        .clone();
+    other.name = "RGB-PAR".to_string();
+    profiles.push(other);
+
+    let mut patch = vec![fixture(1, "4 channel", 0, 1), fixture(2, "4 channel", 0, 5)];
+    patch[1].profile = "Generic/RGB-PAR".to_string();
+
+    mvr::export(&path, &patch, &profiles, &dir).unwrap();
+    let rig = mvr::import(&path).unwrap();
+
+    assert!(rig.warnings.is_empty(), "{:?}", rig.warnings);
+    let mut ids: Vec<String> = rig
+        .gdtf_files
+        .iter()
+        .map(|f| f.report.profile.id())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["Generic/RGB PAR", "Generic/RGB-PAR"]);
+
+    let profiles: Vec<&str> = rig.patch.iter().map(|f| f.profile.as_str()).collect();
+    assert_eq!(profiles, vec!["Generic/RGB PAR", "Generic/RGB-PAR"]);
+
+    fs::remove_dir_all(dir).unwrap();
+}
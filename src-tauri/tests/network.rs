@@ -0,0 +1,207 @@
+use blaulicht_lib::dmx::{
+    merge::{MergeMode, MergeRange, Merger},
+    network::{InputProtocol, NetworkInput, NetworkInputs},
+    Frame, Owner, Owners, UNIVERSE_SIZE,
+};
+
+/// An ArtDmx packet for the given port address.
+fn artnet(net: u8, sub_uni: u8, data: &[u8]) -> Vec<u8> {
+    let mut packet = b"Art-Net\0".to_vec();
+    packet.extend([0x00, 0x50]); // OpDmx, little endian
+    packet.extend([0, 14]); // Protocol version
+    packet.extend([0, 0]); // Sequence, physical
+    packet.extend([sub_uni, net]);
+    packet.extend((data.len() as u16).to_be_bytes());
+    packet.extend(data);
+    packet
+}
+
+const SACN_PREVIEW: u8 = 0x80;
+const SACN_TERMINATED: u8 = 0x40;
+
+/// An E1.31 data packet with a null start code.
+fn sacn(universe: u16, priority: u8, options: u8, data: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0x00, 0x10, 0x00, 0x00];
+    packet.extend(b"ASC-E1.17\0\0\0");
+    packet.extend([0x70, 0x00]); // Root layer flags and length
+    packet.extend(4u32.to_be_bytes());
+    packet.extend([0xAB; 16]); // CID
+    packet.extend([0x70, 0x00]); // Framing layer flags and length
+    packet.extend(2u32.to_be_bytes());
+    packet.extend([0; 64]); // Source name
+    packet.push(priority);
+    packet.extend([0, 0]); // Synchronization address
+    packet.push(0); // Sequence
+    packet.push(options);
+    packet.extend(universe.to_be_bytes());
+    packet.extend([0x70, 0x00]); // DMP layer flags and length
+    packet.extend([0x02, 0xA1]);
+    packet.extend(0u16.to_be_bytes()); // First property address
+    packet.extend(1u16.to_be_bytes()); // Address increment
+    packet.extend((data.len() as u16 + 1).to_be_bytes());
+    packet.push(0); // Start code
+    packet.extend(data);
+    packet
+}
+
+fn inputs(protocol: InputProtocol, external_universe: u16, universe: u16) -> NetworkInputs {
+    let network = NetworkInputs::default();
+    network.set_inputs(vec![NetworkInput {
+        protocol,
+        external_universe,
+        universe,
+    }]);
+    network
+}
+
+#[test]
+fn maps_artnet_port_addresses() {
+    // Net 1, subnet and universe 2: port address 0x102.
+    let network = inputs(InputProtocol::ArtNet, 0x102, 3);
+
+    assert!(network.receive_artnet(&artnet(0, 2, &[1, 2, 3])));
+    assert!(network.frame(3).is_none());
+
+    assert!(network.receive_artnet(&artnet(1, 2, &[1, 2, 3])));
+    let frame = network.frame(3).unwrap();
+    assert_eq!(frame.data[..4], [1, 2, 3, 0]);
+    assert_eq!(frame.protocol, InputProtocol::ArtNet);
+}
+
+#[test]
+fn rejects_invalid_artnet() {
+    let network = inputs(InputProtocol::ArtNet, 0, 0);
+
+    let mut wrong_id = artnet(0, 0, &[255]);
+    wrong_id[..8].copy_from_slice(b"Art-Nex\0");
+    assert!(!network.receive_artnet(&wrong_id));
+
+    // Cut off before the data it announces.
+    let mut short = artnet(0, 0, &[255; 512]);
+    short.truncate(100);
+    assert!(!network.receive_artnet(&short));
+    assert!(!network.receive_artnet(&short[..10]));
+
+    assert!(network.frame(0).is_none());
+}
+
+#[test]
+fn maps_sacn_universes_and_terminates() {
+    let network = inputs(InputProtocol::Sacn, 7, 0);
+
+    assert!(network.receive_sacn(&sacn(7, 100, 0, &[9, 8, 7])));
+    let frame = network.frame(0).unwrap();
+    assert_eq!(frame.data[..4], [9, 8, 7, 0]);
+    assert_eq!(frame.priority, 100);
+
+    // Preview data is not for output.
+    assert!(!network.receive_sacn(&sacn(7, 100, SACN_PREVIEW, &[1])));
+    assert_eq!(network.frame(0).unwrap().data[0], 9);
+
+    assert!(network.receive_sacn(&sacn(7, 100, SACN_TERMINATED, &[])));
+    assert!(network.frame(0).is_none());
+}
+
+#[test]
+fn rejects_invalid_sacn() {
+    let network = inputs(InputProtocol::Sacn, 1, 0);
+
+    let mut wrong_id = sacn(1, 100, 0, &[255]);
+    wrong_id[4] = b'X';
+    assert!(!network.receive_sacn(&wrong_id));
+
+    let mut short = sacn(1, 100, 0, &[255; 512]);
+    short.truncate(200);
+    assert!(!network.receive_sacn(&short));
+    assert!(!network.receive_sacn(&short[..120]));
+
+    assert!(network.frame(0).is_none());
+}
+
+#[test]
+fn keeps_the_higher_sacn_priority() {
+    let network = inputs(InputProtocol::Sacn, 1, 0);
+
+    network.receive_sacn(&sacn(1, 150, 0, &[10]));
+    network.receive_sacn(&sacn(1, 100, 0, &[20]));
+    assert_eq!(network.frame(0).unwrap().data[0], 10);
+}
+
+fn range(start: u16, end: u16, mode: MergeMode) -> MergeRange {
+    MergeRange {
+        universe: 0,
+        start,
+        end,
+        mode,
+        priority: 100,
+    }
+}
+
+fn merge(merger: &mut Merger, network: &NetworkInputs, internal: Frame) -> (Frame, Owners) {
+    let mut frames = vec![internal];
+    let mut owners = vec![[Owner::Engine; UNIVERSE_SIZE]];
+    merger.merge(&mut frames, &mut owners, network);
+    (frames[0], owners[0])
+}
+
+fn frame(values: &[u8]) -> Frame {
+    let mut frame = [0; UNIVERSE_SIZE];
+    frame[..values.len()].copy_from_slice(values);
+    frame
+}
+
+#[test]
+fn merges_htp_within_the_range() {
+    let network = inputs(InputProtocol::Sacn, 1, 0);
+    network.receive_sacn(&sacn(1, 100, 0, &[200, 200, 200]));
+
+    let mut merger = Merger::default();
+    merger.set_ranges(vec![range(1, 2, MergeMode::Htp)]);
+
+    let (frame, owners) = merge(&mut merger, &network, frame(&[100, 250, 100]));
+    assert_eq!(frame[..3], [200, 250, 100]);
+    assert_eq!(owners[..3], [Owner::Sacn, Owner::Engine, Owner::Engine]);
+}
+
+#[test]
+fn merges_ltp_by_the_latest_change() {
+    let network = inputs(InputProtocol::ArtNet, 0, 0);
+    network.receive_artnet(&artnet(0, 0, &[50]));
+
+    let mut merger = Merger::default();
+    merger.set_ranges(vec![range(1, 1, MergeMode::Ltp)]);
+
+    // Nothing changed yet, Blaulicht keeps the channel.
+    assert_eq!(merge(&mut merger, &network, frame(&[100])).0[0], 100);
+
+    network.receive_artnet(&artnet(0, 0, &[60]));
+    let (merged, owners) = merge(&mut merger, &network, frame(&[100]));
+    assert_eq!(merged[0], 60);
+    assert_eq!(owners[0], Owner::ArtNet);
+
+    // Until Blaulicht changes it again.
+    assert_eq!(merge(&mut merger, &network, frame(&[100])).0[0], 60);
+    assert_eq!(merge(&mut merger, &network, frame(&[90])).0[0], 90);
+}
+
+#[test]
+fn merges_by_sacn_priority() {
+    let mut merger = Merger::default();
+    merger.set_ranges(vec![range(1, 2, MergeMode::Priority)]);
+
+    let higher = inputs(InputProtocol::Sacn, 1, 0);
+    higher.receive_sacn(&sacn(1, 150, 0, &[10, 10, 10]));
+    let (merged, _) = merge(&mut merger, &higher, frame(&[100, 100, 100]));
+    assert_eq!(merged[..3], [10, 10, 100]);
+
+    let lower = inputs(InputProtocol::Sacn, 1, 0);
+    lower.receive_sacn(&sacn(1, 50, 0, &[200, 200]));
+    let (merged, _) = merge(&mut merger, &lower, frame(&[100, 100]));
+    assert_eq!(merged[..2], [100, 100]);
+
+    // Equal priorities are merged HTP.
+    let equal = inputs(InputProtocol::Sacn, 1, 0);
+    equal.receive_sacn(&sacn(1, 100, 0, &[50, 150]));
+    let (merged, _) = merge(&mut merger, &equal, frame(&[100, 100]));
+    assert_eq!(merged[..2], [100, 150]);
+}
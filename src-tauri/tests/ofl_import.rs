@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use blaulicht_lib::fixture::{
+    import::{self, ofl},
+    profile::{Attribute, FixtureProfile},
+};
+
+fn sample_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn sample(name: &str) -> String {
+    std::fs::read_to_string(sample_path(name)).unwrap()
+}
+
+fn attributes(profile: &FixtureProfile, mode: &str) -> Vec<(Attribute, Vec<u16>)> {
+    profile
+        .attributes(profile.mode(mode).unwrap())
+        .into_iter()
+        .map(|a| (a.attribute, a.offsets))
+        .collect()
+}
+
+#[test]
+fn imports_channels_and_capabilities() {
+    let report = ofl::parse(&sample("Generic-LED-Spot.json"), "fixtures").unwrap();
+    let profile = &report.profile;
+    profile.validate().unwrap();
+
+    // The manufacturer comes from the fixture, not from the directory it is in.
+    assert_eq!(profile.id(), "generic/LED Spot");
+
+    assert_eq!(profile.channel("Pan").unwrap().attribute, Attribute::Pan);
+    assert_eq!(profile.channel("Pan").unwrap().default_value, 128);
+    assert_eq!(profile.channel("Tilt").unwrap().default_value, 128);
+    assert_eq!(
+        profile.channel("Dimmer").unwrap().attribute,
+        Attribute::Dimmer
+    );
+    assert_eq!(
+        profile.channel("Gobo Wheel").unwrap().attribute,
+        Attribute::Gobo
+    );
+    assert_eq!(
+        profile.channel("Color Wheel").unwrap().attribute,
+        Attribute::Generic
+    );
+
+    let shutter = profile.channel("Shutter").unwrap();
+    assert_eq!(shutter.attribute, Attribute::Strobe);
+    let ranges: Vec<(u8, u8, &str)> = shutter
+        .ranges
+        .iter()
+        .map(|r| (r.from, r.to, r.name.as_str()))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![
+            (0, 9, "ShutterStrobe"),
+            (10, 249, "Strobe slow to fast"),
+            (250, 255, "ShutterStrobe"),
+        ]
+    );
+
+    assert_eq!(report.unmapped, vec!["Color Wheel: WheelSlot"]);
+}
+
+#[test]
+fn imports_modes_with_fine_and_unused_channels() {
+    let report = ofl::parse(&sample("Generic-LED-Spot.json"), "fixtures").unwrap();
+    let profile = &report.profile;
+
+    let modes: Vec<&str> = profile.modes.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(modes, vec!["6-channel", "8-channel"]);
+    assert_eq!(profile.mode("8-channel").unwrap().footprint(), 8);
+
+    let attributes_8bit = attributes(profile, "6-channel");
+    assert_eq!(attributes_8bit[0], (Attribute::Pan, vec![0]));
+    assert_eq!(attributes_8bit[1], (Attribute::Tilt, vec![1]));
+
+    let attributes_16bit = attributes(profile, "8-channel");
+    assert_eq!(attributes_16bit[0], (Attribute::Pan, vec![0, 1]));
+    assert_eq!(attributes_16bit[1], (Attribute::Tilt, vec![2]));
+    assert!(attributes_16bit.contains(&(Attribute::Dimmer, vec![4])));
+}
+
+#[test]
+fn falls_back_to_the_directory_as_manufacturer() {
+    let raw = sample("Generic-LED-Spot.json").replace("\"manufacturerKey\": \"generic\",", "");
+    let report = ofl::parse(&raw, "cameo").unwrap();
+    assert_eq!(report.profile.id(), "cameo/LED Spot");
+}
+
+#[test]
+fn takes_the_manufacturer_only_from_the_repository_layout() {
+    let in_repository = Path::new("/src/open-fixture-library/fixtures/cameo/spot.json");
+    assert_eq!(ofl::manufacturer_directory(in_repository), "cameo");
+
+    let downloaded = Path::new("/home/me/Downloads/spot.json");
+    assert_eq!(ofl::manufacturer_directory(downloaded), "Unknown");
+    assert_eq!(
+        ofl::manufacturer_directory(Path::new("spot.json")),
+        "Unknown"
+    );
+}
+
+#[test]
+fn detects_ofl_by_its_keys() {
+    assert!(ofl::is_ofl(&sample("Generic-LED-Spot.json")));
+    assert!(ofl::is_ofl(r#"{ "name": "Par", "availableChannels": {} }"#));
+
+    let profile = include_str!("../fixtures/generic-dimmer.json");
+    assert!(!ofl::is_ofl(profile));
+    assert!(!ofl::is_ofl("not json"));
+}
+
+#[test]
+fn imports_json_by_its_content() {
+    let report = import::import(&sample_path("Generic-LED-Spot.json")).unwrap();
+    assert_eq!(report.profile.id(), "generic/LED Spot");
+
+    // Blaulicht's own profiles are JSON as well.
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/generic-dimmer.json");
+    let report = import::import(&path).unwrap();
+    assert_eq!(report.profile.id(), "Generic/Dimmer");
+    assert!(report.unmapped.is_empty());
+}
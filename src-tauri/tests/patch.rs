@@ -0,0 +1,165 @@
+use blaulicht_lib::{
+    dmx::MAX_UNIVERSES,
+    fixture::{
+        self,
+        curve::IntensityCurve,
+        movement::Movement,
+        patch::{self, PatchIssue, PatchedFixture, ResolvedFixture},
+        profile::{Attribute, AttributeChannels},
+    },
+};
+
+fn par(id: u32, universe: u16, address: u16) -> PatchedFixture {
+    PatchedFixture {
+        id,
+        name: format!("Par {id}"),
+        profile: "Generic/RGB PAR".to_string(),
+        mode: "4 channel".to_string(),
+        universe,
+        address,
+        position: None,
+        movement: Default::default(),
+        intensity_curve: Default::default(),
+    }
+}
+
+#[test]
+fn accepts_a_clean_patch() {
+    let patch = [par(1, 0, 1), par(2, 0, 5), par(3, 1, 1), par(4, 0, 509)];
+    assert_eq!(patch::validate(&patch, &fixture::builtin()), vec![]);
+    assert_eq!(patch::resolve(&patch, &fixture::builtin()).len(), 4);
+}
+
+#[test]
+fn finds_overlaps_within_a_universe() {
+    let patch = [par(1, 0, 1), par(2, 0, 4), par(3, 1, 2), par(4, 0, 8)];
+    assert_eq!(
+        patch::validate(&patch, &fixture::builtin()),
+        vec![PatchIssue::Overlap { id: 1, other: 2 }]
+    );
+
+    // Overlapping fixtures are still output.
+    assert_eq!(patch::resolve(&patch, &fixture::builtin()).len(), 4);
+}
+
+#[test]
+fn finds_addresses_out_of_range() {
+    let patch = [par(1, 0, 510), par(2, 0, 0)];
+    assert_eq!(
+        patch::validate(&patch, &fixture::builtin()),
+        vec![
+            PatchIssue::OutOfRange {
+                id: 1,
+                address: 510,
+                footprint: 4,
+            },
+            PatchIssue::OutOfRange {
+                id: 2,
+                address: 0,
+                footprint: 4,
+            },
+        ]
+    );
+    assert!(patch::resolve(&patch, &fixture::builtin()).is_empty());
+}
+
+#[test]
+fn finds_universes_out_of_range() {
+    let patch = [
+        par(1, MAX_UNIVERSES - 1, 1),
+        par(2, MAX_UNIVERSES, 1),
+        par(3, u16::MAX, 1),
+    ];
+    assert_eq!(
+        patch::validate(&patch, &fixture::builtin()),
+        vec![
+            PatchIssue::UniverseOutOfRange {
+                id: 2,
+                universe: MAX_UNIVERSES,
+            },
+            PatchIssue::UniverseOutOfRange {
+                id: 3,
+                universe: u16::MAX,
+            },
+        ]
+    );
+
+    let resolved = patch::resolve(&patch, &fixture::builtin());
+    let ids: Vec<u32> = resolved.iter().map(|f| f.id).collect();
+    assert_eq!(ids, vec![1]);
+}
+
+#[test]
+fn finds_duplicate_ids() {
+    let patch = [par(1, 0, 1), par(1, 1, 1)];
+    assert_eq!(
+        patch::validate(&patch, &fixture::builtin()),
+        vec![PatchIssue::DuplicateId { id: 1 }]
+    );
+
+    // Only the first fixture with the id is output.
+    let resolved = patch::resolve(&patch, &fixture::builtin());
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(resolved[0].universe, 0);
+}
+
+#[test]
+fn finds_unknown_profiles_and_modes() {
+    let mut unknown_profile = par(1, 0, 1);
+    unknown_profile.profile = "Nobody/Nothing".to_string();
+    let mut unknown_mode = par(2, 0, 10);
+    unknown_mode.mode = "12 channel".to_string();
+
+    assert_eq!(
+        patch::validate(
+            &[unknown_profile.clone(), unknown_mode.clone()],
+            &fixture::builtin()
+        ),
+        vec![
+            PatchIssue::UnknownProfile {
+                id: 1,
+                profile: "Nobody/Nothing".to_string(),
+            },
+            PatchIssue::UnknownMode {
+                id: 2,
+                mode: "12 channel".to_string(),
+            },
+        ]
+    );
+    assert!(patch::resolve(&[unknown_profile, unknown_mode], &fixture::builtin()).is_empty());
+}
+
+#[test]
+fn includes_fine_channels_in_the_intensity_channels() {
+    let fixture = |id: u32, address: u16, attributes: Vec<(Attribute, Vec<u16>)>| ResolvedFixture {
+        id,
+        universe: 0,
+        address,
+        footprint: attributes.iter().map(|(_, o)| o.len() as u16).sum(),
+        attributes: attributes
+            .into_iter()
+            .map(|(attribute, offsets)| AttributeChannels {
+                attribute,
+                offsets,
+                default_value: 0,
+            })
+            .collect(),
+        movement: Movement::default(),
+        intensity_curve: IntensityCurve::default(),
+    };
+
+    // A spot with a 16 bit dimmer, and a par dimmed through its emitters.
+    let spot = fixture(
+        1,
+        1,
+        vec![(Attribute::Pan, vec![0]), (Attribute::Dimmer, vec![1, 2])],
+    );
+    let par = fixture(
+        2,
+        10,
+        vec![(Attribute::Red, vec![0]), (Attribute::Green, vec![1])],
+    );
+
+    let channels = patch::intensity_channels(&[spot, par]);
+    assert_eq!(channels[&0], vec![2, 3, 10, 11]);
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use blaulicht_lib::fixture::{
+    import::{self, qxf},
+    profile::{Attribute, FixtureProfile},
+};
+
+fn sample(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn attributes(profile: &FixtureProfile, mode: &str) -> Vec<(Attribute, Vec<u16>)> {
+    profile
+        .attributes(profile.mode(mode).unwrap())
+        .into_iter()
+        .map(|a| (a.attribute, a.offsets))
+        .collect()
+}
+
+#[test]
+fn imports_channel_groups_and_colours() {
+    let report = qxf::parse(&sample("Eurolite-LED-PAR-56-RGB.qxf")).unwrap();
+    let profile = &report.profile;
+    profile.validate().unwrap();
+
+    assert_eq!(profile.id(), "Eurolite/LED PAR-56 RGB");
+    assert_eq!(profile.channel("Red").unwrap().attribute, Attribute::Red);
+    assert_eq!(
+        profile.channel("Dimmer").unwrap().attribute,
+        Attribute::Dimmer
+    );
+    assert_eq!(profile.channel("Dimmer").unwrap().default_value, 255);
+    assert_eq!(
+        profile.channel("Strobe").unwrap().attribute,
+        Attribute::Strobe
+    );
+    assert_eq!(
+        profile.channel("Program").unwrap().attribute,
+        Attribute::Generic
+    );
+
+    assert_eq!(report.unmapped, vec!["Program: Colour"]);
+}
+
+#[test]
+fn imports_capabilities_as_ranges() {
+    let report = qxf::parse(&sample("Eurolite-LED-PAR-56-RGB.qxf")).unwrap();
+
+    let strobe = report.profile.channel("Strobe").unwrap();
+    let ranges: Vec<(u8, u8, &str)> = strobe
+        .ranges
+        .iter()
+        .map(|r| (r.from, r.to, r.name.as_str()))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![(0, 15, "Open"), (16, 255, "Strobe slow to fast")]
+    );
+
+    // A single capability spanning the whole channel is not worth a range.
+    assert!(report.profile.channel("Dimmer").unwrap().ranges.is_empty());
+}
+
+#[test]
+fn orders_mode_channels_by_number() {
+    let report = qxf::parse(&sample("Eurolite-LED-PAR-56-RGB.qxf")).unwrap();
+    let profile = &report.profile;
+
+    let modes: Vec<&str> = profile.modes.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(modes, vec!["3 Channel", "6 Channel"]);
+
+    assert_eq!(
+        profile.mode("6 Channel").unwrap().channels,
+        vec!["Dimmer", "Red", "Green", "Blue", "Strobe", "Program"]
+    );
+    assert_eq!(
+        attributes(profile, "3 Channel"),
+        vec![
+            (Attribute::Red, vec![0]),
+            (Attribute::Green, vec![1]),
+            (Attribute::Blue, vec![2]),
+        ]
+    );
+}
+
+#[test]
+fn imports_presets() {
+    let report = qxf::parse(&sample("Generic-Spot-Moving-Head.qxf")).unwrap();
+    let profile = &report.profile;
+    profile.validate().unwrap();
+
+    assert_eq!(profile.channel("Pan").unwrap().attribute, Attribute::Pan);
+    assert_eq!(profile.channel("Tilt").unwrap().attribute, Attribute::Tilt);
+    assert_eq!(
+        profile.channel("Dimmer").unwrap().attribute,
+        Attribute::Dimmer
+    );
+    assert_eq!(
+        profile.channel("Gobo wheel").unwrap().attribute,
+        Attribute::Gobo
+    );
+    assert_eq!(profile.channel("Gobo wheel").unwrap().ranges.len(), 4);
+
+    // Group based channels still work next to preset based ones.
+    let shutter = profile.channel("Shutter").unwrap();
+    assert_eq!(shutter.attribute, Attribute::Strobe);
+    assert_eq!(shutter.default_value, 8);
+
+    assert_eq!(
+        report.unmapped,
+        vec![
+            "Pan/Tilt speed: SpeedPanTiltFastSlow",
+            "Color wheel: ColorWheel",
+            "Reset: Maintenance",
+        ]
+    );
+}
+
+#[test]
+fn links_fine_channels() {
+    let report = qxf::parse(&sample("Generic-Spot-Moving-Head.qxf")).unwrap();
+    let profile = &report.profile;
+
+    assert_eq!(
+        profile.channel("Pan").unwrap().fine_channels,
+        vec!["Pan fine"]
+    );
+    assert_eq!(
+        profile.channel("Tilt").unwrap().fine_channels,
+        vec!["Tilt fine"]
+    );
+
+    let attributes_16bit = attributes(profile, "10 Channel");
+    assert_eq!(attributes_16bit[0], (Attribute::Pan, vec![0, 1]));
+    assert_eq!(attributes_16bit[1], (Attribute::Tilt, vec![2, 3]));
+
+    // Without the fine channels in the mode, pan and tilt are 8 bit.
+    let attributes_8bit = attributes(profile, "8 Channel");
+    assert_eq!(attributes_8bit[0], (Attribute::Pan, vec![0]));
+    assert_eq!(attributes_8bit[1], (Attribute::Tilt, vec![1]));
+}
+
+#[test]
+fn picks_importer_by_extension() {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/Generic-Spot-Moving-Head.qxf");
+    let report = import::import(&path).unwrap();
+    assert_eq!(report.profile.id(), "Generic/Spot Moving Head");
+}
+
+#[test]
+fn rejects_other_xml() {
+    assert!(qxf::parse("<Workspace/>").is_err());
+    assert!(qxf::parse("not xml").is_err());
+}
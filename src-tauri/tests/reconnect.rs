@@ -0,0 +1,51 @@
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use blaulicht_lib::dmx::output::{self, RECONNECT_INTERVAL};
+
+#[test]
+fn tolerates_timeouts() {
+    let timeout = io::Error::new(io::ErrorKind::TimedOut, "write timed out");
+    assert!(!output::port_lost(&timeout));
+}
+
+#[test]
+fn loses_the_port_on_other_errors() {
+    for kind in [
+        io::ErrorKind::BrokenPipe,
+        io::ErrorKind::NotFound,
+        io::ErrorKind::PermissionDenied,
+        io::ErrorKind::Other,
+    ] {
+        assert!(output::port_lost(&io::Error::new(kind, "gone")), "{kind:?}");
+    }
+}
+
+#[test]
+fn backs_off_before_reconnecting() {
+    let lost = Instant::now();
+
+    // Right after the disconnect, and until the interval is over, nothing is tried.
+    assert!(!output::reconnect_due(lost, lost));
+    assert!(!output::reconnect_due(lost, lost + RECONNECT_INTERVAL / 2));
+    assert!(!output::reconnect_due(lost, lost + RECONNECT_INTERVAL));
+
+    let retry = lost + RECONNECT_INTERVAL + Duration::from_millis(1);
+    assert!(output::reconnect_due(lost, retry));
+
+    // A failed attempt starts the next interval.
+    assert!(!output::reconnect_due(
+        retry,
+        retry + Duration::from_millis(10)
+    ));
+    assert!(output::reconnect_due(retry, retry + RECONNECT_INTERVAL * 2));
+}
+
+#[test]
+fn does_not_reconnect_from_the_future() {
+    // Instants taken in another order, e.g. across threads, must not underflow.
+    let now = Instant::now();
+    assert!(!output::reconnect_due(now + Duration::from_secs(5), now));
+}
@@ -0,0 +1,94 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use blaulicht_lib::dmx::{
+    recording::{Player, Recorder},
+    Frame, UNIVERSE_SIZE,
+};
+
+/// Far apart enough to tell the records apart on a busy machine.
+const GAP: Duration = Duration::from_millis(200);
+
+fn scratch_file(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("blaulicht-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.join("show.bldmx")
+}
+
+fn frame(values: &[(usize, u8)]) -> Frame {
+    let mut frame = [0; UNIVERSE_SIZE];
+    for &(index, value) in values {
+        frame[index] = value;
+    }
+    frame
+}
+
+/// Three records per universe: the first frame in full, a small change as a delta
+/// and a change of every channel in full again.
+fn takes() -> [Vec<Frame>; 3] {
+    [
+        vec![frame(&[(0, 255), (511, 1)]), frame(&[(1, 10)])],
+        vec![frame(&[(0, 128), (511, 1)]), frame(&[(1, 10)])],
+        vec![[42; UNIVERSE_SIZE], frame(&[(1, 10), (2, 20)])],
+    ]
+}
+
+fn record(path: &Path) {
+    let mut recorder = Recorder::create(path).unwrap();
+    for (i, frames) in takes().iter().enumerate() {
+        if i > 0 {
+            thread::sleep(GAP);
+        }
+        recorder.record(frames).unwrap();
+        // Unchanged frames are not written again.
+        recorder.record(frames).unwrap();
+    }
+    recorder.finish().unwrap();
+}
+
+#[test]
+fn plays_back_frames_in_time() {
+    let path = scratch_file("recording-round-trip");
+    record(&path);
+    let [first, second, third] = takes();
+
+    let mut player = Player::open(&path, false).unwrap();
+    assert_eq!(player.advance().unwrap(), first);
+
+    // Nothing changes before the next record is due.
+    thread::sleep(GAP / 2);
+    assert_eq!(player.advance().unwrap(), first);
+
+    thread::sleep(GAP);
+    assert_eq!(player.advance().unwrap(), second);
+
+    thread::sleep(GAP);
+    assert_eq!(player.advance().unwrap(), third);
+    assert!(player.finished());
+}
+
+#[test]
+fn loops_back_to_the_start() {
+    let path = scratch_file("recording-loop");
+    record(&path);
+    let [first, _, third] = takes();
+
+    let mut player = Player::open(&path, true).unwrap();
+    thread::sleep(GAP * 5 / 2);
+    assert_eq!(player.advance().unwrap(), third);
+    assert!(!player.finished());
+
+    assert_eq!(player.advance().unwrap(), first);
+}
+
+#[test]
+fn rejects_other_files() {
+    let path = scratch_file("recording-invalid");
+    fs::write(&path, b"not a recording").unwrap();
+    assert!(Player::open(&path, false).is_err());
+}
@@ -0,0 +1,60 @@
+use std::{fs, path::PathBuf};
+
+use blaulicht_lib::{
+    fixture::patch::PatchedFixture,
+    show::{self, Show},
+};
+
+fn scratch_file(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("blaulicht-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(show::SHOW_FILE)
+}
+
+#[test]
+fn saves_and_loads_the_show() {
+    let path = scratch_file("show");
+    assert!(show::load(&path).unwrap().patch.is_empty());
+
+    let show = Show {
+        patch: vec![PatchedFixture {
+            id: 1,
+            name: "Par 1".to_string(),
+            profile: "Generic/RGB PAR".to_string(),
+            mode: "3 channel".to_string(),
+            universe: 0,
+            address: 1,
+            position: None,
+            movement: Default::default(),
+            intensity_curve: Default::default(),
+        }],
+        groups: vec![],
+        routes: vec![],
+    };
+    show::save(&path, &show).unwrap();
+
+    let loaded = show::load(&path).unwrap();
+    assert_eq!(loaded.patch, show.patch);
+}
+
+#[test]
+fn moves_an_invalid_show_out_of_the_way() {
+    let path = scratch_file("show-invalid");
+    fs::write(&path, "{ \"patch\": [ broken").unwrap();
+
+    let show = show::load(&path).unwrap();
+    assert!(show.patch.is_empty());
+    assert!(!path.exists());
+
+    let backup = show::backup_path(&path);
+    assert_eq!(backup.file_name().unwrap(), "show.json.bak");
+    assert_eq!(
+        fs::read_to_string(&backup).unwrap(),
+        "{ \"patch\": [ broken"
+    );
+
+    // Saving the empty show leaves the broken one alone.
+    show::save(&path, &show).unwrap();
+    assert!(backup.exists());
+}
@@ -0,0 +1,77 @@
+use blaulicht_lib::dmx::{enttec_pro_parameters, interfaces::DmxTiming, UNIVERSE_SIZE};
+
+fn timing(break_us: u64, mark_after_break_us: u64) -> DmxTiming {
+    DmxTiming {
+        break_us,
+        mark_after_break_us,
+        ..DmxTiming::default()
+    }
+}
+
+#[test]
+fn clamps_to_the_dmx_minimums() {
+    let clamped = DmxTiming {
+        break_us: 0,
+        mark_after_break_us: 0,
+        inter_frame_gap_us: 0,
+        slots: 0,
+        timeout_ms: 0,
+    }
+    .clamped();
+    assert_eq!(
+        clamped,
+        DmxTiming {
+            break_us: DmxTiming::MIN_BREAK_US,
+            mark_after_break_us: DmxTiming::MIN_MARK_AFTER_BREAK_US,
+            inter_frame_gap_us: 0,
+            slots: 1,
+            timeout_ms: 1,
+        }
+    );
+
+    let at_the_limits = DmxTiming {
+        break_us: 92,
+        mark_after_break_us: 12,
+        inter_frame_gap_us: 1000,
+        slots: UNIVERSE_SIZE as u16,
+        timeout_ms: 1,
+    };
+    assert_eq!(at_the_limits.clamped(), at_the_limits);
+
+    let too_many = DmxTiming {
+        slots: 600,
+        ..DmxTiming::default()
+    };
+    assert_eq!(too_many.clamped().slots, UNIVERSE_SIZE as u16);
+}
+
+#[test]
+fn encodes_enttec_timing_in_widget_units() {
+    // 10.67us per unit, rounded.
+    assert_eq!(enttec_pro_parameters(&timing(107, 32)), [0, 0, 10, 3, 0]);
+    assert_eq!(
+        enttec_pro_parameters(&timing(1000, 1000)),
+        [0, 0, 94, 94, 0]
+    );
+    assert_eq!(
+        enttec_pro_parameters(&timing(1355, 1355)),
+        [0, 0, 127, 127, 0]
+    );
+}
+
+#[test]
+fn clamps_enttec_timing_to_the_widget_range() {
+    // The break is at least 9 units, the MAB at least 1.
+    assert_eq!(enttec_pro_parameters(&timing(92, 12)), [0, 0, 9, 1, 0]);
+    assert_eq!(enttec_pro_parameters(&timing(0, 0)), [0, 0, 9, 1, 0]);
+
+    // Both are at most 127 units, even far beyond what fits in a byte.
+    assert_eq!(
+        enttec_pro_parameters(&timing(1366, 1366)),
+        [0, 0, 127, 127, 0]
+    );
+    assert_eq!(
+        enttec_pro_parameters(&timing(1_000_000, 1_000_000)),
+        [0, 0, 127, 127, 0]
+    );
+}
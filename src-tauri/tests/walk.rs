@@ -0,0 +1,112 @@
+use blaulicht_lib::dmx::{
+    output::FrameBuffer,
+    walk::{Walk, WalkConfig, WalkState},
+    Owner, UNIVERSE_SIZE,
+};
+
+fn walk(start: u16, footprint: u16) -> Walk {
+    Walk::new(WalkConfig {
+        start,
+        footprint,
+        dwell_ms: None,
+        ..WalkConfig::default()
+    })
+}
+
+fn first(walk: &Walk) -> u16 {
+    walk.state().first
+}
+
+#[test]
+fn steps_single_channels_and_wraps_around() {
+    let mut walk = walk(1, 1);
+    assert_eq!(first(&walk), 1);
+
+    walk.step(true);
+    assert_eq!(first(&walk), 2);
+    walk.step(false);
+    walk.step(false);
+    assert_eq!(first(&walk), 512);
+    walk.step(true);
+    assert_eq!(first(&walk), 1);
+}
+
+#[test]
+fn steps_by_the_footprint_from_the_start() {
+    let mut walk = walk(5, 4);
+    assert_eq!(
+        walk.state(),
+        WalkState {
+            universe: 0,
+            first: 5,
+            last: 8,
+        }
+    );
+
+    // The last whole footprint ends at channel 512, then the walk returns to the start.
+    walk.step(false);
+    assert_eq!(first(&walk), 509);
+    assert_eq!(walk.state().last, 512);
+    walk.step(true);
+    assert_eq!(first(&walk), 5);
+}
+
+#[test]
+fn keeps_the_footprint_within_the_universe() {
+    assert_eq!(first(&walk(600, 1)), 512);
+    assert_eq!(walk(0, 0).state().last, 1);
+
+    let whole = walk(3, 1000);
+    assert_eq!((whole.state().first, whole.state().last), (1, 512));
+}
+
+#[test]
+fn advances_only_with_a_dwell_time() {
+    let mut manual = walk(1, 1);
+    assert!(!manual.advance());
+    assert_eq!(first(&manual), 1);
+
+    let mut timed = Walk::new(WalkConfig {
+        dwell_ms: Some(0),
+        ..WalkConfig::default()
+    });
+    assert!(timed.advance());
+    assert_eq!(first(&timed), 2);
+}
+
+#[test]
+fn darkens_the_walked_universe_only() {
+    let walk = Walk::new(WalkConfig {
+        universe: 1,
+        start: 3,
+        footprint: 2,
+        value: 200,
+        dwell_ms: None,
+    });
+    let mut frames = vec![[10; UNIVERSE_SIZE]];
+    let mut owners = vec![[Owner::Engine; UNIVERSE_SIZE]];
+
+    walk.apply(&mut frames, &mut owners);
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0], [10; UNIVERSE_SIZE]);
+    assert_eq!(owners[0], [Owner::Engine; UNIVERSE_SIZE]);
+    assert_eq!(frames[1][..6], [0, 0, 200, 200, 0, 0]);
+    assert_eq!(owners[1], [Owner::Walk; UNIVERSE_SIZE]);
+}
+
+#[test]
+fn leaves_the_engine_output_as_it_was() {
+    let buffer = FrameBuffer::new(1);
+    buffer.write(|frames| frames[0].fill(10));
+    buffer.publish();
+
+    // The walk only ever changes the copy sent out, so stopping it brings back
+    // the engine's values on the next tick.
+    let mut frames = buffer.snapshot();
+    let mut owners = vec![[Owner::Engine; UNIVERSE_SIZE]];
+    walk(1, 1).apply(&mut frames, &mut owners);
+    assert_eq!(frames[0][..2], [255, 0]);
+
+    assert_eq!(buffer.snapshot(), vec![[10; UNIVERSE_SIZE]]);
+}